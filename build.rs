@@ -2,14 +2,17 @@
 // Modifications Copyright (c) 2021-present Crypto.org(licensed under the Apache License, Version 2.0)
 
 //! Build CosmosSDK/Tendermint/IBC proto files. This build script clones the CosmosSDK version
-//! specified in the COSMOS_REV constant and then uses that to build the required
+//! specified in the COSMOS_REV constant (and the IBC version specified in IBC_GO_REV) and then uses that to build the required
 //! proto files for further compilation. This is based on the proto-compiler code
 //! in github.com/informalsystems/ibc-rs
 
 #[cfg(feature = "grpc")]
 mod grpc {
+    use prost::Message;
+    use prost_types::FileDescriptorSet;
     use regex::Regex;
     use std::{
+        env,
         ffi::OsStr,
         fs::{self, create_dir_all, remove_dir_all},
         io,
@@ -19,9 +22,13 @@ mod grpc {
     use walkdir::WalkDir;
 
     /// The Cosmos commit or tag to be cloned and used to build the proto files
-    const COSMOS_REV: &str = "v0.42.0";
+    const COSMOS_REV: &str = "v0.44.5";
     // TODO: use version tag
-    const TENDERMINT_REV: &str = "v0.34.8";
+    const TENDERMINT_REV: &str = "v0.34.14";
+    /// The ibc-go commit or tag to be cloned and used to build the IBC proto files
+    const IBC_GO_REV: &str = "v2.0.2";
+    /// The chain-main commit or tag to be cloned and used to build its custom module proto files
+    const CHAIN_MAIN_REV: &str = "v3.3.3";
 
     // All paths must end with a / and either be absolute or include a ./ to reference the current
     // working directory.
@@ -31,6 +38,13 @@ mod grpc {
     /// Directory where the submodule is located
     const COSMOS_SDK_DIR: &str = "./cosmos-sdk-go";
     const TENDERMINT_DIR: &str = "./tendermint";
+    const IBC_GO_DIR: &str = "./ibc-go";
+    const CHAIN_MAIN_DIR: &str = "./chain-main";
+    /// Repositories the submodules are cloned from when they are not checked out (see `.gitmodules`)
+    const COSMOS_SDK_URL: &str = "https://github.com/cosmos/cosmos-sdk.git";
+    const TENDERMINT_URL: &str = "https://github.com/tendermint/tendermint.git";
+    const IBC_GO_URL: &str = "https://github.com/cosmos/ibc-go.git";
+    const CHAIN_MAIN_URL: &str = "https://github.com/crypto-org-chain/chain-main.git";
     /// A temporary directory for proto building
     const TMP_BUILD_DIR: &str = "./protobuf/";
 
@@ -41,6 +55,11 @@ mod grpc {
     const EXCLUDED_PROTO_PACKAGES: &[&str] = &["gogoproto", "google"];
     /// Regex for locating instances of `tendermint-proto` in prost/tonic build output
     const TENDERMINT_PROTO_REGEX: &str = "(super::)+tendermint";
+    /// Package and message of `StakeAuthorization`, its `validators` oneof clashes with its
+    /// nested `Validators` message in the generated module
+    const STAKE_AUTHORIZATION: (&str, &str) = ("cosmos.staking.v1beta1", "StakeAuthorization");
+    /// Name the `StakeAuthorization` oneof is generated with (oneof names are not on the wire)
+    const STAKE_AUTHORIZATION_ONEOF: &str = "policy";
     /// Attribute preceeding a Tonic client definition
     const TONIC_CLIENT_ATTRIBUTE: &str = "#[doc = r\" Generated client implementations.\"]";
     /// Attributes to add to gRPC clients
@@ -66,6 +85,7 @@ mod grpc {
         compile_cosmos_protos(&tmp_build_dir);
         compile_proto_services(&tmp_build_dir);
         compile_tendermint_protos(&tmp_build_dir);
+        compile_chain_main_protos(&tmp_build_dir);
         copy_generated_files(&tmp_build_dir, &proto_dir);
         fs::remove_dir_all(&tmp_build_dir).unwrap();
     }
@@ -82,11 +102,22 @@ mod grpc {
     }
 
     fn update_submodule() {
-        run_git(&["submodule", "update", "--init"]);
-        run_git(&["-C", COSMOS_SDK_DIR, "fetch"]);
-        run_git(&["-C", COSMOS_SDK_DIR, "reset", "--hard", COSMOS_REV]);
-        run_git(&["-C", TENDERMINT_DIR, "fetch"]);
-        run_git(&["-C", TENDERMINT_DIR, "reset", "--hard", TENDERMINT_REV]);
+        run_git(["submodule", "update", "--init"]);
+        checkout(COSMOS_SDK_DIR, COSMOS_SDK_URL, COSMOS_REV);
+        checkout(TENDERMINT_DIR, TENDERMINT_URL, TENDERMINT_REV);
+        checkout(IBC_GO_DIR, IBC_GO_URL, IBC_GO_REV);
+        checkout(CHAIN_MAIN_DIR, CHAIN_MAIN_URL, CHAIN_MAIN_REV);
+    }
+
+    /// checks out `rev` in the submodule `dir`, cloning it from `url` if it is not checked out
+    /// (git would otherwise run in this repository)
+    fn checkout(dir: &str, url: &str, rev: &str) {
+        if Path::new(dir).join(".git").exists() {
+            run_git(["-C", dir, "fetch", "--tags"]);
+            run_git(["-C", dir, "reset", "--hard", rev]);
+        } else {
+            run_git(["clone", "--branch", rev, url, dir]);
+        }
     }
 
     fn output_sdk_version(out_dir: &Path) {
@@ -94,6 +125,10 @@ mod grpc {
         fs::write(path, COSMOS_REV).unwrap();
         let path = out_dir.join("TENDERMINT_COMMIT");
         fs::write(path, TENDERMINT_REV).unwrap();
+        let path = out_dir.join("IBC_GO_COMMIT");
+        fs::write(path, IBC_GO_REV).unwrap();
+        let path = out_dir.join("CHAIN_MAIN_COMMIT");
+        fs::write(path, CHAIN_MAIN_REV).unwrap();
     }
 
     fn find_proto_files(proto_paths: Vec<String>) -> Vec<PathBuf> {
@@ -118,7 +153,7 @@ mod grpc {
     fn compile_tendermint_protos(out_dir: &Path) {
         let tendermint_dir = Path::new(TENDERMINT_DIR);
         let proto_paths = vec![format!("{}/proto/tendermint/rpc", tendermint_dir.display())];
-        let proto_includes_paths = vec![
+        let proto_includes_paths = [
             format!("{}/proto/", tendermint_dir.display()),
             format!("{}/third_party/proto", tendermint_dir.display()),
         ];
@@ -140,19 +175,27 @@ mod grpc {
 
     fn compile_cosmos_protos(out_dir: &Path) {
         let sdk_dir = Path::new(COSMOS_SDK_DIR);
+        let ibc_dir = Path::new(IBC_GO_DIR);
 
         // Paths
         let proto_paths = vec![
-            format!("{}/proto/ibc", sdk_dir.display()),
+            format!("{}/proto/ibc", ibc_dir.display()),
+            format!("{}/proto/cosmos/auth", sdk_dir.display()),
+            format!("{}/proto/cosmos/authz", sdk_dir.display()),
             format!("{}/proto/cosmos/tx", sdk_dir.display()),
             format!("{}/proto/cosmos/bank", sdk_dir.display()),
             format!("{}/proto/cosmos/base", sdk_dir.display()),
+            format!("{}/proto/cosmos/distribution", sdk_dir.display()),
+            format!("{}/proto/cosmos/gov", sdk_dir.display()),
             format!("{}/proto/cosmos/staking", sdk_dir.display()),
+            format!("{}/proto/cosmos/vesting", sdk_dir.display()),
         ];
 
-        let proto_includes_paths = vec![
+        let proto_includes_paths = [
             format!("{}/proto", sdk_dir.display()),
             format!("{}/third_party/proto", sdk_dir.display()),
+            format!("{}/proto", ibc_dir.display()),
+            format!("{}/third_party/proto", ibc_dir.display()),
         ];
 
         // List available proto files
@@ -165,6 +208,40 @@ mod grpc {
         let mut config = prost_build::Config::default();
         config.out_dir(out_dir);
         config.extern_path(".tendermint", "crate::proto::tendermint");
+        config
+            .file_descriptor_set_path(descriptor_set("cosmos", &protos, &includes))
+            .skip_protoc_run();
+
+        if let Err(e) = config.compile_protos(&protos, &includes) {
+            eprintln!("[error] couldn't compile protos: {}", e);
+            panic!("protoc failed!");
+        }
+    }
+
+    fn compile_chain_main_protos(out_dir: &Path) {
+        let chain_main_dir = Path::new(CHAIN_MAIN_DIR);
+        let sdk_dir = Path::new(COSMOS_SDK_DIR);
+
+        // Paths
+        let proto_paths = vec![format!("{}/proto/nft", chain_main_dir.display())];
+
+        let proto_includes_paths = [
+            format!("{}/proto", chain_main_dir.display()),
+            format!("{}/third_party/proto", chain_main_dir.display()),
+            format!("{}/proto", sdk_dir.display()),
+            format!("{}/third_party/proto", sdk_dir.display()),
+        ];
+
+        // List available proto files
+        let protos = find_proto_files(proto_paths);
+
+        // List available paths for dependencies
+        let includes: Vec<PathBuf> = proto_includes_paths.iter().map(PathBuf::from).collect();
+
+        // Compile all proto files
+        let mut config = prost_build::Config::default();
+        config.out_dir(out_dir);
+        config.extern_path(".cosmos", "crate::proto::cosmos");
 
         if let Err(e) = config.compile_protos(&protos, &includes) {
             eprintln!("[error] couldn't compile protos: {}", e);
@@ -189,8 +266,20 @@ mod grpc {
             .collect::<Vec<_>>();
 
         let proto_services_path = [
+            // account types are decoded from the `Any` of the account query
+            sdk_dir.join("proto/cosmos/auth/v1beta1/auth.proto"),
             sdk_dir.join("proto/cosmos/auth/v1beta1/query.proto"),
+            sdk_dir.join("proto/cosmos/authz/v1beta1/query.proto"),
+            sdk_dir.join("proto/cosmos/authz/v1beta1/tx.proto"),
+            // the bank package is regenerated with its client, so all its messages are listed
+            sdk_dir.join("proto/cosmos/bank/v1beta1/authz.proto"),
+            sdk_dir.join("proto/cosmos/bank/v1beta1/bank.proto"),
+            sdk_dir.join("proto/cosmos/bank/v1beta1/query.proto"),
+            sdk_dir.join("proto/cosmos/bank/v1beta1/tx.proto"),
             sdk_dir.join("proto/cosmos/staking/v1beta1/query.proto"),
+            sdk_dir.join("proto/cosmos/staking/v1beta1/staking.proto"),
+            sdk_dir.join("proto/cosmos/staking/v1beta1/authz.proto"),
+            sdk_dir.join("proto/cosmos/staking/v1beta1/tx.proto"),
             sdk_dir.join("proto/cosmos/tx/v1beta1/service.proto"),
             sdk_dir.join("proto/cosmos/tx/v1beta1/tx.proto"),
         ];
@@ -202,18 +291,63 @@ mod grpc {
             .collect::<Vec<_>>();
 
         // Compile all proto client for GRPC services
-        println!("[info ] Compiling proto clients for GRPC services!");
+        // servers are generated for the mock node and client tests
+        println!("[info ] Compiling proto clients and servers for GRPC services!");
+        let mut config = prost_build::Config::default();
+        config
+            .file_descriptor_set_path(descriptor_set("services", &services, &includes))
+            .skip_protoc_run();
         tonic_build::configure()
             .build_client(true)
-            .build_server(false)
+            .build_server(true)
             .format(true)
             .out_dir(out_dir)
-            .compile(&services, &includes)
+            .compile_with_config(config, &services, &includes)
             .unwrap();
 
         println!("[info ] => Done!");
     }
 
+    /// Runs protoc like prost-build does and renames the `StakeAuthorization` oneof in the
+    /// descriptors, returns the path of the descriptor set written to `OUT_DIR`
+    fn descriptor_set(
+        name: &str,
+        protos: &[impl AsRef<OsStr>],
+        includes: &[impl AsRef<OsStr>],
+    ) -> PathBuf {
+        let path = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join(format!("{}.bin", name));
+        let mut protoc = process::Command::new(prost_build::protoc());
+        protoc
+            .arg("--include_imports")
+            .arg("--include_source_info")
+            .arg("-o")
+            .arg(&path);
+        for include in includes {
+            protoc.arg("-I").arg(include);
+        }
+        protoc.arg("-I").arg(prost_build::protoc_include());
+        protoc.args(protos);
+
+        let exit_status = protoc.status().expect("protoc exit status missing");
+        if !exit_status.success() {
+            panic!("protoc exited with error code: {:?}", exit_status.code());
+        }
+
+        let mut descriptors = FileDescriptorSet::decode(fs::read(&path).unwrap().as_slice())
+            .expect("invalid descriptor set");
+        let (package, message_name) = STAKE_AUTHORIZATION;
+        descriptors
+            .file
+            .iter_mut()
+            .filter(|file| file.package() == package)
+            .flat_map(|file| file.message_type.iter_mut())
+            .filter(|message| message.name() == message_name)
+            .flat_map(|message| message.oneof_decl.iter_mut())
+            .for_each(|oneof| oneof.name = Some(STAKE_AUTHORIZATION_ONEOF.to_owned()));
+        fs::write(&path, descriptors.encode_to_vec()).unwrap();
+        path
+    }
+
     fn copy_generated_files(from_dir: &Path, to_dir: &Path) {
         println!(
             "[info ] Copying generated files into '{}'...",
@@ -221,8 +355,8 @@ mod grpc {
         );
 
         // Remove old compiled files
-        remove_dir_all(&to_dir).unwrap_or_default();
-        create_dir_all(&to_dir).unwrap();
+        remove_dir_all(to_dir).unwrap_or_default();
+        create_dir_all(to_dir).unwrap();
 
         let mut filenames = Vec::new();
 
@@ -258,7 +392,7 @@ mod grpc {
             }
         }
 
-        let contents = fs::read_to_string(src)?;
+        let contents = fs::read_to_string(&src)?;
 
         // `prost-build` output references types from `tendermint-proto` crate
         // relative paths, which we need to munge into `tendermint_proto` in
@@ -1,10 +1,9 @@
 use anyhow::Error;
 
 use chainlib::client::Client;
-use chainlib::constant::ACCOUNT_ADDRESS_PREFIX;
 use chainlib::hd_wallet::mnemonic::Mnemonic;
 use chainlib::key_service::private_key_service::PrivateKeyService;
-use chainlib::key_service::KeyService;
+use chainlib::network::Network;
 use chainlib::proto::cosmos::base::v1beta1::Coin;
 use chainlib::proto::cosmos::tx::v1beta1::Fee;
 use chainlib::tx_builder::TxBuilder;
@@ -12,46 +11,55 @@ use chainlib::tx_builder::TxBuilder;
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     env_logger::init();
-    // base api url is set as `address = "tcp://0.0.0.0:1317"` in $CHAIN_MAIND_HOME/config/app.toml
-    let base_api_url = "http://127.0.0.1:1317".to_string();
+    // a local chain-maind with the chain id "test", use `Network::mainnet()` or
+    // `Network::testnet()` for the public networks
+    let mut network = Network::devnet();
+    network.set_chain_id("test");
     // grpc url is set in `grpc_laddr` in $CHAIN_MAIND_HOME/config/config.toml
     let grpc_url = "http://127.0.0.1:1234".to_string();
-    let client = Client::new(base_api_url, grpc_url);
+    let mut client = Client::new(network.api_url.clone(), grpc_url);
+    client.set_cosmos_grpc_url(network.grpc_url.clone());
 
     let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
     let mnemonic = Mnemonic::from_str(words, None).unwrap();
-    let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
-    let chain_id = "test".into();
+    let key_service = PrivateKeyService::new_from_mnemonic_for_network(mnemonic, &network)?;
+    let gas_limit = 300000;
     let fee = Fee {
         amount: vec![Coin {
-            denom: "basecro".to_string(),
-            amount: 10000.to_string(),
+            denom: network.base_denom.clone(),
+            amount: network.fee_amount(gas_limit).to_string(),
         }],
-        gas_limit: 300000,
+        gas_limit,
         payer: "".to_string(),
         granter: "".to_string(),
     };
 
     let timeout_height = 0;
-    let mut builder = TxBuilder::new(key_service, chain_id, None, timeout_height, Some(fee));
+    let mut builder = TxBuilder::with_network(
+        key_service,
+        network.clone(),
+        None,
+        timeout_height,
+        Some(fee),
+    );
 
-    let self_address = builder.key_service.address().unwrap();
-    let address_str = self_address.to_bech32(ACCOUNT_ADDRESS_PREFIX);
+    let self_address = builder.sender_address()?;
+    let address_str = self_address.to_string();
 
     // update account info
     let (account_number, sequence) = client.get_account_info(&address_str).await.unwrap();
 
     // add msg
-    let to_address = "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9".into();
-    let amount = Coin {
-        denom: "basecro".into(),
-        amount: 100000000.to_string(),
-    };
-    let msg = builder.create_msg(to_address, amount).unwrap();
+    let to_address = network.parse_address("cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9")?;
+    let amount = builder.base_coin(100000000);
+    let msg = builder.create_msg(&to_address, amount)?;
     builder
         .add_message(msg)
         .set_account_number(account_number)
         .set_sequence(sequence);
+    // replace the fixed fee with one estimated from a simulation
+    let fee = builder.estimate_fee(&client).await?;
+    println!("estimated fee: {:?}", fee);
 
     let b64_tx = builder.build().await.unwrap();
     let tx_bytes = base64::decode(b64_tx).unwrap();
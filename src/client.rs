@@ -1,91 +1,1545 @@
 use crate::error::Error;
 #[cfg(feature = "grpc")]
+use crate::proto::cosmos::auth::v1beta1::query_client::QueryClient as AuthQueryClient;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::auth::v1beta1::QueryAccountRequest;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::authz::v1beta1::query_client::QueryClient as AuthzQueryClient;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::authz::v1beta1::{Grant, QueryGrantsRequest};
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::bank::v1beta1::query_client::QueryClient as BankQueryClient;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::bank::v1beta1::{
+    QueryAllBalancesRequest, QueryBalanceRequest, QueryDenomMetadataRequest, QuerySupplyOfRequest,
+    QueryTotalSupplyRequest,
+};
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::base::abci::v1beta1::GasInfo;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::tx::v1beta1::service_client::ServiceClient as TxServiceClient;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::tx::v1beta1::{
+    BroadcastMode, BroadcastTxRequest, GetTxRequest, SimulateRequest,
+};
+#[cfg(feature = "grpc")]
 use crate::proto::tendermint::rpc::grpc::broadcast_api_client::BroadcastApiClient;
 #[cfg(feature = "grpc")]
-use crate::proto::tendermint::rpc::grpc::{RequestBroadcastTx, ResponseBroadcastTx};
+use crate::proto::tendermint::rpc::grpc::RequestBroadcastTx;
+use crate::tx_builder::payout::{PayoutBatch, PayoutReport};
+use crate::types::account::Account;
+use crate::types::basic::SyncMode;
+#[cfg(feature = "grpc")]
+use crate::types::broadcast::SDK_CODESPACE;
+use crate::types::broadcast::{BroadcastResult, SdkError};
+use crate::types::coin::{Coin, Coins};
+use crate::types::denom::{
+    is_ibc_denom, DenomMetadata, DenomRegistry, DenomTrace, IBC_DENOM_PREFIX,
+};
 #[cfg(not(feature = "grpc"))]
 use crate::types::transaction::Transaction;
+use crate::utils::codec::tx_hash;
+use endpoint::{
+    is_transport_error, next_backoff, Endpoint, EndpointHealth, EndpointPool,
+    DEFAULT_MAX_HEIGHT_LAG, DEFAULT_MAX_RETRIES, DEFAULT_RETRY_BACKOFF,
+};
+use futures::TryStreamExt;
+use pagination::Pagination;
 #[cfg(not(feature = "grpc"))]
 use serde::Serialize;
+#[cfg(feature = "grpc")]
+use std::convert::TryFrom;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// full node endpoints with health checks and failover
+pub mod endpoint;
+/// paginated list queries as streams
+pub mod pagination;
+/// Tendermint JSON-RPC client
+pub mod rpc;
+/// account number and sequence caching for concurrent senders
+pub mod sequence;
+/// staking module queries
+#[cfg(feature = "grpc")]
+mod staking;
+/// Tendermint RPC websocket event subscriptions
+#[cfg(feature = "subscription")]
+pub mod subscription;
+
+/// HTTP servers replaying canned responses for client tests
+#[cfg(test)]
+mod mock;
+
+/// gRPC status code the REST gateway returns for unknown accounts
+const GRPC_NOT_FOUND: u64 = 5;
+
+/// Tendermint mempool error for a transaction it has already received,
+/// returned as a gRPC status instead of a transaction result
+#[cfg(feature = "grpc")]
+const TX_IN_CACHE: &str = "tx already exists in cache";
 
-/// API client
+/// first delay between queries while waiting for a transaction
+const WAIT_FOR_TX_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// the delay between queries doubles up to this limit
+const WAIT_FOR_TX_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// status and JSON body of a REST response
+async fn read_json(
+    response: reqwest::Response,
+) -> Result<(reqwest::StatusCode, serde_json::Value), Error> {
+    let status = response.status();
+    let text = response.text().await?;
+    let body = serde_json::from_str(&text).map_err(|_| {
+        Error::ClientError(format!(
+            "invalid response, status: {}, body: {}",
+            status, text
+        ))
+    })?;
+    Ok((status, body))
+}
+
+/// node error from a REST error or tx response body
+fn node_error(body: &serde_json::Value) -> Error {
+    let raw_log = ["raw_log", "message", "error"]
+        .iter()
+        .find_map(|key| body[key].as_str())
+        .unwrap_or_default();
+    Error::NodeError {
+        code: body["code"].as_u64().unwrap_or_default() as u32,
+        codespace: body["codespace"].as_str().unwrap_or_default().to_string(),
+        raw_log: raw_log.to_string(),
+    }
+}
+
+/// u64 encoded as a JSON string
+fn json_u64(value: &serde_json::Value, name: &str) -> Result<u64, Error> {
+    value
+        .as_str()
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| Error::ClientError(format!("invalid {} in response: {}", name, value)))
+}
+
+/// percent-encoded path segment of a REST path parameter; grpc-gateway routes on the decoded
+/// path, so values containing a slash like IBC denominations still do not match a route
+fn path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn coin_from_json(coin: serde_json::Value) -> Result<Coin, Error> {
+    serde_json::from_value(coin.clone())
+        .map_err(|e| Error::ClientError(format!("invalid coin {} in response: {}", coin, e)))
+}
+
+#[cfg(feature = "grpc")]
+fn transport_error(e: tonic::transport::Error) -> Error {
+    Error::TransportError(format!("connect to grpc server failed: {}", e))
+}
+
+/// error of a failed gRPC call, unavailable servers and timeouts are transport errors
+#[cfg(feature = "grpc")]
+fn status_error(status: tonic::Status) -> Error {
+    if matches!(
+        status.code(),
+        tonic::Code::Unavailable | tonic::Code::DeadlineExceeded
+    ) {
+        return Error::TransportError(format!("grpc request failed: {}", status));
+    }
+    if status.message().contains(TX_IN_CACHE) {
+        return Error::NodeError {
+            code: 19,
+            codespace: SDK_CODESPACE.to_string(),
+            raw_log: status.message().to_string(),
+        };
+    }
+    Error::NodeError {
+        code: status.code() as u32,
+        codespace: String::new(),
+        raw_log: status.message().to_string(),
+    }
+}
+
+/// committed transaction with `hash` queried from the REST api at `api_url`
+async fn get_tx_at(
+    http: &reqwest::Client,
+    api_url: &str,
+    hash: &str,
+) -> Result<Option<BroadcastResult>, Error> {
+    let url = format!("{}/cosmos/tx/v1beta1/txs/{}", api_url, path_segment(hash));
+    let (status, response) = read_json(http.get(&url).send().await?).await?;
+    // {'code': 5, 'message': 'rpc error: code = NotFound desc = tx not found: ...', 'details': []}
+    if status == reqwest::StatusCode::NOT_FOUND || response["code"] == GRPC_NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(node_error(&response));
+    }
+    // {'tx': {...}, 'tx_response': {'height': '1024', 'txhash': '...', 'code': 0, ...}}
+    BroadcastResult::from_json(&response["tx_response"]).map(Some)
+}
+
+/// committed transaction with the base64 `signature`, found by the "tx.signature" event
+/// the Cosmos SDK emits for every signature, queried from the REST api at `api_url`
+#[cfg(not(feature = "grpc"))]
+async fn find_tx_by_signature(
+    http: &reqwest::Client,
+    api_url: &str,
+    signature: &str,
+) -> Result<Option<BroadcastResult>, Error> {
+    let url = format!("{}/cosmos/tx/v1beta1/txs", api_url);
+    let query = [("events", format!("tx.signature='{}'", signature))];
+    let (status, response) = read_json(http.get(&url).query(&query).send().await?).await?;
+    if !status.is_success() {
+        return Err(node_error(&response));
+    }
+    // {'txs': [...], 'tx_responses': [{'height': '1024', 'txhash': '...', 'code': 0, ...}], 'pagination': {...}}
+    match response["tx_responses"].get(0) {
+        Some(tx_response) => BroadcastResult::from_json(tx_response).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// bank module metadata of a denomination in a REST response
+fn denom_metadata_from_json(metadata: &serde_json::Value) -> Result<DenomMetadata, Error> {
+    // {'description': '...', 'denom_units': [{'denom': 'basecro', 'exponent': 0, 'aliases': []}, {'denom': 'cro', 'exponent': 8, 'aliases': []}], 'base': 'basecro', 'display': 'cro', 'name': '', 'symbol': ''}
+    let text = |key: &str| metadata[key].as_str().unwrap_or_default().to_string();
+    let units: Vec<(String, u32)> = metadata["denom_units"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|unit| {
+            let denom = unit["denom"].as_str().unwrap_or_default().to_string();
+            (denom, unit["exponent"].as_u64().unwrap_or_default() as u32)
+        })
+        .collect();
+    if text("base").is_empty() {
+        return Err(Error::ClientError(format!(
+            "invalid denom metadata response: {}",
+            metadata
+        )));
+    }
+    Ok(DenomMetadata::from_bank_metadata(
+        text("base"),
+        text("display"),
+        text("symbol"),
+        &units,
+    ))
+}
+
+/// a broadcast retried after a transport error is rejected as already in the mempool
+/// if an earlier attempt reached a node, the transaction was accepted then
+fn accept_duplicate(mut result: BroadcastResult) -> BroadcastResult {
+    let error = SdkError::from_code(&result.codespace, result.code, &result.raw_log);
+    if error == Some(SdkError::TxInMempoolCache) {
+        result.code = 0;
+        result.codespace.clear();
+    }
+    result
+}
+
+/// `accept_duplicate` for a gRPC broadcast of the transaction with `hash` rejected with a status
+#[cfg(feature = "grpc")]
+fn accept_duplicate_status(
+    hash: String,
+    result: Result<BroadcastResult, Error>,
+) -> Result<BroadcastResult, Error> {
+    match result {
+        Err(e) if e.sdk_error() == Some(SdkError::TxInMempoolCache) => Ok(BroadcastResult {
+            hash,
+            ..Default::default()
+        }),
+        result => result,
+    }
+}
+
+/// API client, clones share the endpoints and their health
+#[derive(Clone)]
 pub struct Client {
-    // base api url is set in section `address` in $CHAIN_MAIND_HOME/config/app.toml
-    base_api_url: String,
-    // grpc url is set in section `grpc_laddr` in $CHAIN_MAIND_HOME/config/config.toml
-    #[cfg(feature = "grpc")]
-    grpc_url: String,
+    pool: Arc<EndpointPool>,
+    http: reqwest::Client,
+    max_retries: usize,
+    retry_backoff: Duration,
+    max_height_lag: u64,
+    load_balancing: bool,
 }
 
 impl Client {
     /// create a new client
-    #[cfg(any(not(feature = "grpc")))]
+    #[cfg(not(feature = "grpc"))]
     pub fn new(base_api_url: String) -> Self {
-        Self { base_api_url }
+        Self::with_pool(EndpointPool::new(vec![Endpoint::new(base_api_url)]))
     }
 
     #[cfg(feature = "grpc")]
     pub fn new(base_api_url: String, grpc_url: String) -> Self {
-        Self {
+        Self::with_pool(EndpointPool::new(vec![Endpoint::new(
             base_api_url,
             grpc_url,
+        )]))
+    }
+
+    /// create a client failing over between full nodes, `endpoints` are in order of preference
+    pub fn with_endpoints(endpoints: Vec<Endpoint>) -> Result<Self, Error> {
+        if endpoints.is_empty() {
+            return Err(Error::InputError("no endpoints given".to_string()));
+        }
+        Ok(Self::with_pool(EndpointPool::new(endpoints)))
+    }
+
+    fn with_pool(pool: EndpointPool) -> Self {
+        Self {
+            pool: Arc::new(pool),
+            http: reqwest::Client::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            max_height_lag: DEFAULT_MAX_HEIGHT_LAG,
+            load_balancing: false,
+        }
+    }
+
+    /// set the cosmos gRPC url used for module queries of the first endpoint,
+    /// for this client and its clones
+    #[cfg(feature = "grpc")]
+    pub fn set_cosmos_grpc_url(&mut self, cosmos_grpc_url: String) -> &mut Self {
+        self.pool.set_cosmos_grpc_url(0, cosmos_grpc_url);
+        self
+    }
+
+    /// set the HTTP client of REST requests shared by all calls,
+    /// e.g. with timeouts, TLS roots, a proxy or default headers such as API keys
+    pub fn set_http_client(&mut self, http: reqwest::Client) -> &mut Self {
+        self.http = http;
+        self
+    }
+
+    /// set the timeout of gRPC requests, for this client and its clones
+    #[cfg(feature = "grpc")]
+    pub fn set_grpc_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.pool.set_grpc_timeout(timeout);
+        self
+    }
+
+    /// set the number of retries of a request failing with a transport error,
+    /// each on the next healthy endpoint
+    pub fn set_max_retries(&mut self, max_retries: usize) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// set the delay before the first retry, it doubles with each further retry
+    pub fn set_retry_backoff(&mut self, retry_backoff: Duration) -> &mut Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// set the number of blocks an endpoint may lag behind the highest one and stay healthy
+    pub fn set_max_height_lag(&mut self, max_height_lag: u64) -> &mut Self {
+        self.max_height_lag = max_height_lag;
+        self
+    }
+
+    /// spread requests over the healthy endpoints in turn instead of using the first one
+    pub fn set_load_balancing(&mut self, load_balancing: bool) -> &mut Self {
+        self.load_balancing = load_balancing;
+        self
+    }
+
+    /// endpoints in order of preference
+    pub fn endpoints(&self) -> Vec<Endpoint> {
+        self.pool.endpoints()
+    }
+
+    /// query the status of all endpoints and only use the reachable, synced ones which lag
+    /// at most `max_height_lag` blocks behind the highest one;
+    /// an endpoint marked unhealthy by a transport error is only used again after a check
+    /// finds it healthy (or while all endpoints are unhealthy), see `spawn_health_checks`
+    pub async fn check_health(&self) -> Vec<EndpointHealth> {
+        self.pool
+            .check_health(&self.http, self.max_height_lag)
+            .await
+    }
+
+    /// run `check_health` every `interval` in a background task, the first check right away;
+    /// the checks update the endpoints of all clones until the task is aborted
+    pub fn spawn_health_checks(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut checks = tokio::time::interval(interval);
+            loop {
+                checks.tick().await;
+                client.check_health().await;
+            }
+        })
+    }
+
+    /// run `request` on the preferred endpoint, a transport error marks it unhealthy
+    /// and the request is retried with backoff on the next one
+    async fn with_failover<T, F, Fut>(&self, request: F) -> Result<T, Error>
+    where
+        F: FnMut(Endpoint) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        self.with_failover_on(|_| true, request).await
+    }
+
+    /// `with_failover` among the endpoints for which `usable` is true
+    async fn with_failover_on<T, U, F, Fut>(&self, usable: U, mut request: F) -> Result<T, Error>
+    where
+        U: Fn(&Endpoint) -> bool,
+        F: FnMut(Endpoint) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut delay = self.retry_backoff;
+        let mut attempt = 0;
+        loop {
+            let index = self
+                .pool
+                .pick(self.load_balancing, attempt, &usable)
+                .ok_or_else(|| Error::ClientError("no endpoint for the request".to_string()))?;
+            let endpoint = self.pool.endpoint(index);
+            let api_url = endpoint.api_url.clone();
+            match request(endpoint).await {
+                Err(e) if is_transport_error(&e) => {
+                    self.pool.set_healthy(index, false);
+                    if attempt >= self.max_retries {
+                        return Err(e);
+                    }
+                    log::warn!("request to {} failed, retrying: {}", api_url, e);
+                    tokio::time::sleep(delay).await;
+                    delay = next_backoff(delay);
+                    attempt += 1;
+                }
+                result => {
+                    self.pool.set_healthy(index, true);
+                    return result;
+                }
+            }
         }
     }
 
+    /// GET a REST path, e.g. "/cosmos/bank/v1beta1/supply", with failover
+    async fn rest_get(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<(reqwest::StatusCode, serde_json::Value), Error> {
+        self.with_failover(|endpoint| {
+            let url = format!("{}{}", endpoint.api_url, path);
+            let request = self.http.get(&url).query(query);
+            async move { read_json(request.send().await?).await }
+        })
+        .await
+    }
+
+    /// whether module queries can go through gRPC
+    #[cfg(feature = "grpc")]
+    fn has_cosmos_grpc(&self) -> bool {
+        self.endpoints()
+            .iter()
+            .any(|endpoint| endpoint.cosmos_grpc_url.is_some())
+    }
+
+    /// channel to the gRPC server at `url`, connected on first use and shared afterwards
+    #[cfg(feature = "grpc")]
+    async fn channel(&self, url: String) -> Result<tonic::transport::Channel, Error> {
+        if let Some(channel) = self.pool.channel(&url) {
+            return Ok(channel);
+        }
+        let mut endpoint = tonic::transport::Endpoint::from_shared(url.clone())
+            .map_err(|e| Error::InputError(format!("invalid grpc url {}: {}", url, e)))?;
+        if let Some(timeout) = self.pool.grpc_timeout() {
+            endpoint = endpoint.timeout(timeout);
+        }
+        let channel = endpoint.connect().await.map_err(transport_error)?;
+        self.pool.add_channel(url, channel.clone());
+        Ok(channel)
+    }
+
+    /// send `request` with `call` to the cosmos gRPC url of the preferred endpoint having one,
+    /// failing over to the next one like REST requests
+    #[cfg(feature = "grpc")]
+    async fn cosmos_grpc<Req, Resp, C, Fut>(&self, request: Req, call: C) -> Result<Resp, Error>
+    where
+        Req: Clone,
+        C: Fn(tonic::transport::Channel, Req) -> Fut,
+        Fut: Future<Output = Result<tonic::Response<Resp>, tonic::Status>>,
+    {
+        let (request, call) = (&request, &call);
+        self.with_failover_on(
+            |endpoint| endpoint.cosmos_grpc_url.is_some(),
+            move |endpoint| async move {
+                let channel = self
+                    .channel(endpoint.cosmos_grpc_url.unwrap_or_default())
+                    .await?;
+                let response = call(channel, request.clone()).await;
+                response
+                    .map(tonic::Response::into_inner)
+                    .map_err(status_error)
+            },
+        )
+        .await
+    }
+
     /// get account number and sequence number
     pub async fn get_account_info(&self, address: &str) -> Result<(u64, u64), Error> {
-        let url = format!(
-            "{}/cosmos/auth/v1beta1/accounts/{}",
-            self.base_api_url, address
+        let account = self.get_account(address).await?;
+        Ok((account.base().account_number, account.base().sequence))
+    }
+
+    /// get the account with its public key, module or vesting details,
+    /// queried through gRPC if the cosmos gRPC url is set
+    pub async fn get_account(&self, address: &str) -> Result<Account, Error> {
+        #[cfg(feature = "grpc")]
+        if self.has_cosmos_grpc() {
+            let request = QueryAccountRequest {
+                address: address.to_string(),
+            };
+            let response = self
+                .cosmos_grpc(request, |channel, request| async move {
+                    AuthQueryClient::new(channel).account(request).await
+                })
+                .await;
+            let response = match response {
+                Err(Error::NodeError { code, .. }) if code == tonic::Code::NotFound as u32 => {
+                    return Err(Error::AccountNotFound(address.to_string()));
+                }
+                response => response?,
+            };
+            let account = response
+                .account
+                .ok_or_else(|| Error::ClientError("account response without account".into()))?;
+            return Account::try_from(account);
+        }
+        let path = format!("/cosmos/auth/v1beta1/accounts/{}", path_segment(address));
+        let (status, response) = self.rest_get(&path, &[]).await?;
+        // {'code': 5, 'message': 'rpc error: code = NotFound desc = account cro1... not found: key not found', 'details': []}
+        if status == reqwest::StatusCode::NOT_FOUND || response["code"] == GRPC_NOT_FOUND {
+            return Err(Error::AccountNotFound(address.to_string()));
+        }
+        if !status.is_success() {
+            return Err(node_error(&response));
+        }
+        // {'account': {'@type': '/cosmos.auth.v1beta1.BaseAccount', 'address': 'cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf', 'pub_key': {'@type': '/cosmos.crypto.secp256k1.PubKey', 'key': 'AntL+UxMyJ9NZ9DGLp2v7a3dlSxiNXMaItyOXSRw8iYi'}, 'account_number': '9', 'sequence': '25'}}
+        Account::from_json(&response["account"])
+    }
+
+    /// get authz grants given by `granter` to `grantee`, optionally only for `msg_type_url`
+    #[cfg(feature = "grpc")]
+    pub async fn get_grants(
+        &self,
+        granter: &str,
+        grantee: &str,
+        msg_type_url: Option<&str>,
+    ) -> Result<Vec<Grant>, Error> {
+        let (granter, grantee) = (granter.to_string(), grantee.to_string());
+        let msg_type_url = msg_type_url.unwrap_or_default().to_string();
+        self.paginate_grpc(
+            Pagination::default(),
+            move |page| QueryGrantsRequest {
+                granter: granter.clone(),
+                grantee: grantee.clone(),
+                msg_type_url: msg_type_url.clone(),
+                pagination: Some(page),
+            },
+            |channel, request| async move { AuthzQueryClient::new(channel).grants(request).await },
+            |response| (response.grants, response.pagination),
+        )
+        .try_collect()
+        .await
+    }
+
+    /// simulate a transaction, e.g. from `TxBuilder::simulation_tx`, and return its gas usage
+    #[cfg(feature = "grpc")]
+    pub async fn simulate(&self, tx_bytes: Vec<u8>) -> Result<GasInfo, Error> {
+        let request = SimulateRequest {
+            tx_bytes,
+            ..Default::default()
+        };
+        let response = self
+            .cosmos_grpc(request, |channel, request| async move {
+                TxServiceClient::new(channel).simulate(request).await
+            })
+            .await?;
+        response
+            .gas_info
+            .ok_or_else(|| Error::ClientError("simulate response without gas info".to_string()))
+    }
+
+    /// get the IBC denom trace of an "ibc/<hash>" denomination
+    pub async fn get_denom_trace(&self, ibc_denom: &str) -> Result<DenomTrace, Error> {
+        let hash = if is_ibc_denom(ibc_denom) {
+            &ibc_denom[IBC_DENOM_PREFIX.len()..]
+        } else {
+            ibc_denom
+        };
+        let path = format!("/ibc/apps/transfer/v1/denom_traces/{}", path_segment(hash));
+        let (status, response) = self.rest_get(&path, &[]).await?;
+        if !status.is_success() {
+            return Err(node_error(&response));
+        }
+        // {'denom_trace': {'path': 'transfer/channel-0', 'base_denom': 'uatom'}}
+        serde_json::from_value(response["denom_trace"].clone())
+            .map_err(|e| Error::ClientError(format!("invalid denom trace response: {}", e)))
+    }
+
+    /// metadata of a denomination on the chain `chain_id` the client is connected to,
+    /// unknown IBC denom traces are queried and added to `registry`
+    pub async fn resolve_denom(
+        &self,
+        registry: &mut DenomRegistry,
+        chain_id: &str,
+        denom: &str,
+    ) -> Result<Option<DenomMetadata>, Error> {
+        if is_ibc_denom(denom) && registry.trace(chain_id, denom).is_none() {
+            let trace = self.get_denom_trace(denom).await?;
+            registry.add_trace(chain_id, trace);
+        }
+        Ok(registry.metadata(chain_id, denom).cloned())
+    }
+
+    /// GET a REST query and return the JSON field `field` of the response
+    async fn get_json(&self, path: &str, field: &str) -> Result<serde_json::Value, Error> {
+        let (status, response) = self.rest_get(path, &[]).await?;
+        if !status.is_success() {
+            return Err(node_error(&response));
+        }
+        Ok(response[field].clone())
+    }
+
+    /// all balances of an account, queried through gRPC if the cosmos gRPC url is set
+    pub async fn get_balances(&self, address: &str) -> Result<Coins, Error> {
+        #[cfg(feature = "grpc")]
+        if self.has_cosmos_grpc() {
+            let address = address.to_string();
+            let coins: Vec<ProtoCoin> = self
+                .paginate_grpc(
+                    Pagination::default(),
+                    move |page| QueryAllBalancesRequest {
+                        address: address.clone(),
+                        pagination: Some(page),
+                    },
+                    |channel, request| async move {
+                        BankQueryClient::new(channel).all_balances(request).await
+                    },
+                    |response| (response.balances, response.pagination),
+                )
+                .try_collect()
+                .await?;
+            return Coins::try_from(coins);
+        }
+        let path = format!("/cosmos/bank/v1beta1/balances/{}", path_segment(address));
+        let coins: Vec<Coin> = self
+            .paginate_rest(&path, "balances", Pagination::default())
+            .try_collect()
+            .await?;
+        Coins::new(coins)
+    }
+
+    /// balance of an account in `denom`, zero if the account has none
+    pub async fn get_balance(&self, address: &str, denom: &str) -> Result<Coin, Error> {
+        #[cfg(feature = "grpc")]
+        if self.has_cosmos_grpc() {
+            let request = QueryBalanceRequest {
+                address: address.to_string(),
+                denom: denom.to_string(),
+            };
+            let response = self
+                .cosmos_grpc(request, |channel, request| async move {
+                    BankQueryClient::new(channel).balance(request).await
+                })
+                .await?;
+            return match response.balance {
+                Some(balance) => Coin::try_from(balance),
+                None => Coin::new(0, denom),
+            };
+        }
+        // {'balance': {'denom': 'basecro', 'amount': '100000000'}}
+        let path = format!(
+            "/cosmos/bank/v1beta1/balances/{}/by_denom",
+            path_segment(address)
         );
-        let response = reqwest::get(&url)
-            .await?
-            .json::<serde_json::Value>()
+        let (status, response) = self
+            .rest_get(&path, &[("denom", denom.to_string())])
             .await?;
-        // {'account': {'@type': '/cosmos.auth.v1beta1.BaseAccount', 'address': 'cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf', 'pub_key': {'@type': '/cosmos.crypto.secp256k1.PubKey', 'key': 'AntL+UxMyJ9NZ9DGLp2v7a3dlSxiNXMaItyOXSRw8iYi'}, 'account_number': '9', 'sequence': '25'}}
-        let account_number = response["account"]["account_number"]
-            .as_str()
-            .unwrap()
-            .parse()
-            .unwrap();
-        let sequence = response["account"]["sequence"]
-            .as_str()
-            .unwrap()
-            .parse()
-            .unwrap();
-        Ok((account_number, sequence))
+        if !status.is_success() {
+            return Err(node_error(&response));
+        }
+        coin_from_json(response["balance"].clone())
+    }
+
+    /// total supply of all denominations
+    pub async fn get_total_supply(&self) -> Result<Coins, Error> {
+        #[cfg(feature = "grpc")]
+        if self.has_cosmos_grpc() {
+            let coins: Vec<ProtoCoin> = self
+                .paginate_grpc(
+                    Pagination::default(),
+                    |page| QueryTotalSupplyRequest {
+                        pagination: Some(page),
+                    },
+                    |channel, request| async move {
+                        BankQueryClient::new(channel).total_supply(request).await
+                    },
+                    |response| (response.supply, response.pagination),
+                )
+                .try_collect()
+                .await?;
+            return Coins::try_from(coins);
+        }
+        let coins: Vec<Coin> = self
+            .paginate_rest(
+                "/cosmos/bank/v1beta1/supply",
+                "supply",
+                Pagination::default(),
+            )
+            .try_collect()
+            .await?;
+        Coins::new(coins)
     }
 
+    /// total supply of `denom`; through REST the supply of a denomination containing a slash,
+    /// e.g. "ibc/<hash>", is taken from the list of all denominations, grpc-gateway has no route for it
+    pub async fn get_supply_of(&self, denom: &str) -> Result<Coin, Error> {
+        #[cfg(feature = "grpc")]
+        if self.has_cosmos_grpc() {
+            let request = QuerySupplyOfRequest {
+                denom: denom.to_string(),
+            };
+            let response = self
+                .cosmos_grpc(request, |channel, request| async move {
+                    BankQueryClient::new(channel).supply_of(request).await
+                })
+                .await?;
+            return match response.amount {
+                Some(amount) => Coin::try_from(amount),
+                None => Coin::new(0, denom),
+            };
+        }
+        if denom.contains('/') {
+            let supply = self.get_total_supply().await?;
+            return Coin::new(supply.amount_of(denom), denom);
+        }
+        // {'amount': {'denom': 'basecro', 'amount': '3000000000000000000'}}
+        let path = format!("/cosmos/bank/v1beta1/supply/{}", path_segment(denom));
+        coin_from_json(self.get_json(&path, "amount").await?)
+    }
+
+    /// metadata of a base denomination registered in the bank module; through REST the metadata
+    /// of a denomination containing a slash is looked up in the list of all metadata
+    pub async fn get_denom_metadata(&self, denom: &str) -> Result<DenomMetadata, Error> {
+        #[cfg(feature = "grpc")]
+        if self.has_cosmos_grpc() {
+            let request = QueryDenomMetadataRequest {
+                denom: denom.to_string(),
+            };
+            let response = self
+                .cosmos_grpc(request, |channel, request| async move {
+                    BankQueryClient::new(channel).denom_metadata(request).await
+                })
+                .await?;
+            return response.metadata.map(DenomMetadata::from).ok_or_else(|| {
+                Error::ClientError(format!("no metadata of denom {} in response", denom))
+            });
+        }
+        if denom.contains('/') {
+            let metadatas: Vec<serde_json::Value> = self
+                .paginate_rest(
+                    "/cosmos/bank/v1beta1/denoms_metadata",
+                    "metadatas",
+                    Pagination::default(),
+                )
+                .try_collect()
+                .await?;
+            return metadatas
+                .iter()
+                .find(|metadata| metadata["base"] == denom)
+                .map(denom_metadata_from_json)
+                .unwrap_or_else(|| {
+                    Err(Error::ClientError(format!(
+                        "no metadata of denom {}",
+                        denom
+                    )))
+                });
+        }
+        // {'metadata': {'base': 'basecro', 'display': 'cro', 'denom_units': [...], ...}}
+        let path = format!(
+            "/cosmos/bank/v1beta1/denoms_metadata/{}",
+            path_segment(denom)
+        );
+        denom_metadata_from_json(&self.get_json(&path, "metadata").await?)
+    }
+
+    /// height of the latest block
+    pub async fn latest_height(&self) -> Result<u64, Error> {
+        let (status, response) = self
+            .rest_get("/cosmos/base/tendermint/v1beta1/blocks/latest", &[])
+            .await?;
+        if !status.is_success() {
+            return Err(node_error(&response));
+        }
+        // {'block_id': {...}, 'block': {'header': {'chain_id': 'chainmain-1', 'height': '1024', ...}, ...}}
+        json_u64(&response["block"]["header"]["height"], "block height")
+    }
+
+    /// committed transaction with upper case hex `hash`, `None` if it is not in a block (yet),
+    /// queried through gRPC if the cosmos gRPC url is set
     #[cfg(feature = "grpc")]
-    pub async fn broadcast_tx(self, tx: Vec<u8>) -> ResponseBroadcastTx {
+    pub async fn get_tx(&self, hash: &str) -> Result<Option<BroadcastResult>, Error> {
+        if !self.has_cosmos_grpc() {
+            return self.get_tx_rest(hash).await;
+        }
+        let request = GetTxRequest {
+            hash: hash.to_string(),
+        };
+        let response = self
+            .cosmos_grpc(request, |channel, request| async move {
+                TxServiceClient::new(channel).get_tx(request).await
+            })
+            .await;
+        match response {
+            Ok(response) => Ok(response.tx_response.as_ref().map(BroadcastResult::from)),
+            Err(Error::NodeError { code, .. }) if code == tonic::Code::NotFound as u32 => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// committed transaction with upper case hex `hash`, `None` if it is not in a block (yet)
+    #[cfg(not(feature = "grpc"))]
+    pub async fn get_tx(&self, hash: &str) -> Result<Option<BroadcastResult>, Error> {
+        self.get_tx_rest(hash).await
+    }
+
+    async fn get_tx_rest(&self, hash: &str) -> Result<Option<BroadcastResult>, Error> {
+        self.with_failover(|endpoint| {
+            let hash = hash.to_string();
+            async move { get_tx_at(&self.http, &endpoint.api_url, &hash).await }
+        })
+        .await
+    }
+
+    /// poll with backoff until the transaction is committed, fails with `Error::Timeout` after `timeout`
+    pub async fn wait_for_tx(
+        &self,
+        hash: &str,
+        timeout: Duration,
+    ) -> Result<BroadcastResult, Error> {
+        self.wait_for(hash, timeout, None).await
+    }
+
+    /// like `wait_for_tx`, but also stops once the chain reaches `timeout_height`
+    /// without including the transaction, e.g. the timeout height of the transaction
+    pub async fn wait_for_tx_until_height(
+        &self,
+        hash: &str,
+        timeout: Duration,
+        timeout_height: u64,
+    ) -> Result<BroadcastResult, Error> {
+        self.wait_for(hash, timeout, Some(timeout_height)).await
+    }
+
+    async fn wait_for(
+        &self,
+        hash: &str,
+        timeout: Duration,
+        timeout_height: Option<u64>,
+    ) -> Result<BroadcastResult, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut delay = WAIT_FOR_TX_INITIAL_DELAY;
+        loop {
+            // the height is queried first, so a transaction of that block is found below
+            let height = match timeout_height {
+                Some(_) => Some(self.latest_height().await?),
+                None => None,
+            };
+            if let Some(result) = self.get_tx(hash).await? {
+                return Ok(result);
+            }
+            if let (Some(height), Some(timeout_height)) = (height, timeout_height) {
+                if height >= timeout_height {
+                    return Err(Error::Timeout(format!(
+                        "transaction {} not committed until height {}",
+                        hash, timeout_height
+                    )));
+                }
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout(format!(
+                    "transaction {} not committed within {:?}",
+                    hash, timeout
+                )));
+            }
+            tokio::time::sleep(delay.min(deadline - now)).await;
+            delay = (delay * 2).min(WAIT_FOR_TX_MAX_DELAY);
+        }
+    }
+
+    /// broadcast a protobuf encoded transaction in sync mode and wait until it is committed,
+    /// through gRPC if the cosmos gRPC url is set, otherwise through REST;
+    /// fails with `Error::NodeError` if the transaction is rejected or fails
+    #[cfg(feature = "grpc")]
+    pub async fn broadcast_and_confirm(
+        &self,
+        tx_bytes: Vec<u8>,
+        timeout: Duration,
+    ) -> Result<BroadcastResult, Error> {
+        let result = if self.has_cosmos_grpc() {
+            self.broadcast_tx_with_mode(tx_bytes, SyncMode::Sync)
+                .await?
+        } else {
+            self.broadcast_tx_rest(&tx_bytes, SyncMode::Sync).await?
+        };
+        self.wait_for_tx(&result.hash, timeout).await?.into_result()
+    }
+
+    /// broadcast a transaction and wait until it is committed,
+    /// fails with `Error::NodeError` if the transaction is rejected or fails
+    #[cfg(not(feature = "grpc"))]
+    pub async fn broadcast_and_confirm<M: Serialize>(
+        &self,
+        tx: Transaction<M>,
+        timeout: Duration,
+    ) -> Result<BroadcastResult, Error> {
+        let result = self.broadcast_tx(tx).await?;
+        self.wait_for_tx(&result.hash, timeout).await?.into_result()
+    }
+
+    /// broadcast payout batches in order, stopping at the first failed one
+    #[cfg(feature = "grpc")]
+    pub async fn broadcast_payouts<P>(
+        &self,
+        batches: Vec<PayoutBatch<P, String>>,
+    ) -> Vec<PayoutReport<P, BroadcastResult>> {
+        let mut reports = vec![];
+        let mut failed = false;
+        for batch in batches {
+            let result = if failed {
+                Err(Error::ClientError(
+                    "not broadcasted, a previous batch failed".to_string(),
+                ))
+            } else {
+                match base64::decode(&batch.tx) {
+                    Ok(tx) => self.broadcast_tx(tx).await,
+                    Err(e) => Err(Error::SerializeError(format!(
+                        "invalid base64 transaction, decode error: {:?}",
+                        e
+                    ))),
+                }
+            };
+            failed = result.is_err();
+            reports.push(PayoutReport {
+                sequence: batch.sequence,
+                payouts: batch.payouts,
+                result,
+            });
+        }
+        reports
+    }
+
+    /// committed transaction with `hash` queried from `endpoint`,
+    /// through gRPC if it has a cosmos gRPC url
+    #[cfg(feature = "grpc")]
+    async fn get_tx_on(
+        &self,
+        endpoint: &Endpoint,
+        hash: &str,
+    ) -> Result<Option<BroadcastResult>, Error> {
+        let url = match &endpoint.cosmos_grpc_url {
+            Some(url) => url.clone(),
+            None => return get_tx_at(&self.http, &endpoint.api_url, hash).await,
+        };
+        let request = GetTxRequest {
+            hash: hash.to_string(),
+        };
+        let response = TxServiceClient::new(self.channel(url).await?)
+            .get_tx(request)
+            .await
+            .map_err(status_error);
+        match response {
+            Ok(response) => Ok(response
+                .into_inner()
+                .tx_response
+                .as_ref()
+                .map(BroadcastResult::from)),
+            Err(Error::NodeError { code, .. }) if code == tonic::Code::NotFound as u32 => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// broadcast transaction using the Tendermint gRPC broadcast API, which waits for the block,
+    /// fails with `Error::NodeError` if the transaction is rejected in check tx or deliver tx;
+    /// after a transport error it is only broadcasted again if its hash is not committed yet,
+    /// then already being in the mempool is no error
+    #[cfg(feature = "grpc")]
+    pub async fn broadcast_tx(&self, tx: Vec<u8>) -> Result<BroadcastResult, Error> {
+        let hash = tx_hash(&tx);
         let request = RequestBroadcastTx { tx };
-        let mut client = BroadcastApiClient::connect(self.grpc_url)
+        let mut attempts = 0;
+        self.with_failover(|endpoint| {
+            let retry = attempts > 0;
+            attempts += 1;
+            let (request, hash) = (request.clone(), hash.clone());
+            async move {
+                if retry {
+                    if let Some(result) = self.get_tx_on(&endpoint, &hash).await? {
+                        return Ok(result);
+                    }
+                }
+                let channel = self.channel(endpoint.grpc_url).await?;
+                let result = BroadcastApiClient::new(channel)
+                    .broadcast_tx(request)
+                    .await
+                    .map(|response| {
+                        BroadcastResult::from_response(hash.clone(), response.get_ref())
+                    })
+                    .map_err(status_error);
+                if retry {
+                    accept_duplicate_status(hash, result.map(accept_duplicate))
+                } else {
+                    result
+                }
+            }
+        })
+        .await?
+        .into_result()
+    }
+
+    /// broadcast a protobuf encoded transaction through the Cosmos SDK tx service,
+    /// fails with `Error::NodeError` if the transaction is rejected;
+    /// after a transport error it is only broadcasted again if its hash is not committed yet
+    #[cfg(feature = "grpc")]
+    pub async fn broadcast_tx_with_mode(
+        &self,
+        tx_bytes: Vec<u8>,
+        mode: SyncMode,
+    ) -> Result<BroadcastResult, Error> {
+        let hash = tx_hash(&tx_bytes);
+        let request = BroadcastTxRequest {
+            tx_bytes,
+            mode: BroadcastMode::from(mode) as i32,
+        };
+        let mut attempts = 0;
+        self.with_failover_on(
+            |endpoint| endpoint.cosmos_grpc_url.is_some(),
+            |endpoint| {
+                let retry = attempts > 0;
+                attempts += 1;
+                let (request, hash) = (request.clone(), hash.clone());
+                async move {
+                    if retry {
+                        if let Some(result) = self.get_tx_on(&endpoint, &hash).await? {
+                            return Ok(result);
+                        }
+                    }
+                    let channel = self
+                        .channel(endpoint.cosmos_grpc_url.unwrap_or_default())
+                        .await?;
+                    let result = TxServiceClient::new(channel)
+                        .broadcast_tx(request)
+                        .await
+                        .map_err(status_error)
+                        .and_then(|response| {
+                            response
+                                .get_ref()
+                                .tx_response
+                                .as_ref()
+                                .map(BroadcastResult::from)
+                                .ok_or_else(|| {
+                                    Error::ClientError(
+                                        "broadcast response without tx response".to_string(),
+                                    )
+                                })
+                        });
+                    if retry {
+                        accept_duplicate_status(hash, result.map(accept_duplicate))
+                    } else {
+                        result
+                    }
+                }
+            },
+        )
+        .await?
+        .into_result()
+    }
+
+    /// broadcast a protobuf encoded transaction through the REST endpoint of the Cosmos SDK tx service,
+    /// fails with `Error::NodeError` if the transaction is rejected;
+    /// after a transport error it is only broadcasted again if its hash is not committed yet
+    pub async fn broadcast_tx_rest(
+        &self,
+        tx_bytes: &[u8],
+        mode: SyncMode,
+    ) -> Result<BroadcastResult, Error> {
+        let hash = tx_hash(tx_bytes);
+        let body = serde_json::json!({
+            "tx_bytes": base64::encode(tx_bytes),
+            "mode": mode.broadcast_mode_name(),
+        });
+        let mut attempts = 0;
+        self.with_failover(|endpoint| {
+            let retry = attempts > 0;
+            attempts += 1;
+            let url = format!("{}/cosmos/tx/v1beta1/txs", endpoint.api_url);
+            let request = self.http.post(&url).json(&body);
+            let hash = hash.clone();
+            async move {
+                if retry {
+                    if let Some(result) = get_tx_at(&self.http, &endpoint.api_url, &hash).await? {
+                        return Ok(result);
+                    }
+                }
+                let (status, response) = read_json(request.send().await?).await?;
+                if !status.is_success() {
+                    return Err(node_error(&response));
+                }
+                // {'tx_response': {'height': '0', 'txhash': '...', 'code': 0, 'raw_log': '[]', ...}}
+                let result = BroadcastResult::from_json(&response["tx_response"])?;
+                Ok(if retry {
+                    accept_duplicate(result)
+                } else {
+                    result
+                })
+            }
+        })
+        .await?
+        .into_result()
+    }
+
+    /// broadcast transaction using JSON-RPC,
+    /// fails with `Error::NodeError` if the transaction is rejected;
+    /// after a transport error a signed transaction is only broadcasted again
+    /// if no committed transaction has its signature
+    #[cfg(not(feature = "grpc"))]
+    pub async fn broadcast_tx<M: Serialize>(
+        &self,
+        tx: Transaction<M>,
+    ) -> Result<BroadcastResult, Error> {
+        let signature = tx.tx.signatures.first().map(|s| s.signature.clone());
+        let mut attempts = 0;
+        self.with_failover(|endpoint| {
+            let retry = attempts > 0;
+            attempts += 1;
+            let request = self
+                .http
+                .post(format!("{}/txs", endpoint.api_url))
+                .header("Content-Type", "application/json")
+                .json(&tx);
+            let signature = signature.clone();
+            async move {
+                if let (true, Some(signature)) = (retry, signature) {
+                    let found =
+                        find_tx_by_signature(&self.http, &endpoint.api_url, &signature).await?;
+                    if let Some(result) = found {
+                        return Ok(result);
+                    }
+                }
+                let (status, response) = read_json(request.send().await?).await?;
+                if !status.is_success() {
+                    return Err(node_error(&response));
+                }
+                let result = BroadcastResult::from_json(&response)?;
+                Ok(if retry {
+                    accept_duplicate(result)
+                } else {
+                    result
+                })
+            }
+        })
+        .await?
+        .into_result()
+    }
+
+    /// broadcast payout batches in order using JSON-RPC, stopping at the first failed one
+    #[cfg(not(feature = "grpc"))]
+    pub async fn broadcast_payouts<P, M: Serialize>(
+        &self,
+        batches: Vec<PayoutBatch<P, Transaction<M>>>,
+    ) -> Vec<PayoutReport<P, BroadcastResult>> {
+        let mut reports = vec![];
+        let mut failed = false;
+        for batch in batches {
+            let result = if failed {
+                Err(Error::ClientError(
+                    "not broadcasted, a previous batch failed".to_string(),
+                ))
+            } else {
+                self.broadcast_tx(batch.tx).await
+            };
+            failed = result.is_err();
+            reports.push(PayoutReport {
+                sequence: batch.sequence,
+                payouts: batch.payouts,
+                result,
+            });
+        }
+        reports
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::mock::{mock_server, mock_server_sequence};
+    use super::*;
+
+    #[cfg(not(feature = "grpc"))]
+    fn client(base_api_url: String) -> Client {
+        Client::new(base_api_url)
+    }
+
+    #[cfg(feature = "grpc")]
+    fn client(base_api_url: String) -> Client {
+        Client::new(base_api_url, "http://127.0.0.1:1".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_get_account_info() {
+        let url = mock_server(
+            "200 OK",
+            r#"{"account":{"@type":"/cosmos.auth.v1beta1.BaseAccount","address":"cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf","account_number":"9","sequence":"25"}}"#,
+        )
+        .await;
+        let info = client(url)
+            .get_account_info("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
             .await
-            .expect("connect to grpc server failed");
+            .unwrap();
+        assert_eq!(info, (9, 25));
+
+        let url = mock_server(
+            "404 Not Found",
+            r#"{"code":5,"message":"rpc error: code = NotFound desc = account cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf not found: key not found","details":[]}"#,
+        )
+        .await;
+        let result = client(url)
+            .get_account_info("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .await;
+        assert!(
+            matches!(result, Err(Error::AccountNotFound(address)) if address == "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+        );
+
+        let url = mock_server(
+            "400 Bad Request",
+            r#"{"code":3,"message":"decoding bech32 failed: invalid checksum","details":[]}"#,
+        )
+        .await;
+        let result = client(url).get_account_info("cro1invalid").await;
+        assert!(matches!(result, Err(Error::NodeError { code: 3, .. })));
+
+        let url = mock_server("200 OK", r#"{"account":{}}"#).await;
+        let result = client(url).get_account_info("cro1").await;
+        assert!(matches!(result, Err(Error::ClientError(_))));
+
+        let result = client("http://127.0.0.1:1".to_string())
+            .get_account_info("cro1")
+            .await;
+        assert!(matches!(result, Err(Error::TransportError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_shared_http_client() {
+        fn assert_shareable<T: Send + Sync + Clone + 'static>() {}
+        assert_shareable::<Client>();
 
-        let request = tonic::Request::new(request.clone());
-        let response = client.broadcast_tx(request).await.unwrap();
-        response.into_inner()
+        let (url, mut requests) = mock_server_sequence(vec![(
+            "200 OK",
+            r#"{"balance":{"denom":"basecro","amount":"100000000"}}"#,
+        )])
+        .await;
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-api-key", "secret".parse().unwrap());
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let mut client = client(url);
+        client.set_http_client(http);
+        let client = std::sync::Arc::new(client);
+        let balance = tokio::spawn({
+            let client = client.clone();
+            async move {
+                client
+                    .get_balance("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf", "basecro")
+                    .await
+            }
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(balance.amount(), 100000000);
+        let request = requests.recv().await.unwrap();
+        assert!(request.contains("x-api-key: secret"));
     }
-    /// broadcast transaction using JSON-RPC
+
+    #[tokio::test]
+    async fn test_get_account() {
+        let url = mock_server(
+            "200 OK",
+            r#"{"account":{"@type":"/cosmos.vesting.v1beta1.DelayedVestingAccount","base_vesting_account":{"base_account":{"address":"cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf","pub_key":null,"account_number":"12","sequence":"0"},"original_vesting":[{"denom":"basecro","amount":"500000000"}],"delegated_free":[],"delegated_vesting":[],"end_time":"1700000000"}}}"#,
+        )
+        .await;
+        let account = client(url)
+            .get_account("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .await
+            .unwrap();
+        assert_eq!(account.base().account_number, 12);
+        let balances: Coins = "8cro".parse().unwrap();
+        assert_eq!(
+            account.spendable_coins(&balances, 1600000000),
+            "3cro".parse().unwrap()
+        );
+        assert_eq!(account.spendable_coins(&balances, 1700000000), balances);
+    }
+
     #[cfg(not(feature = "grpc"))]
-    pub async fn broadcast_tx<M: Serialize>(&self, tx: Transaction<M>) -> Result<String, Error> {
-        let url = format!("{}/txs", self.base_api_url);
-        let client = reqwest::Client::new();
-        let response = client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&tx)
-            .send()
-            .await?;
-        if response.status() != reqwest::StatusCode::OK {
-            return Err(Error::ClientError(format!(
-                "send failed, response: {:?}",
-                response
-            )));
+    #[tokio::test]
+    async fn test_broadcast_tx() {
+        use crate::types::basic::{Fee, SyncMode};
+        use crate::types::broadcast::SdkError;
+        use crate::types::transaction::Tx;
+
+        let tx: Transaction<serde_json::Value> = Transaction {
+            tx: Tx {
+                messages: vec![],
+                fee: Fee::default(),
+                memo: String::new(),
+                signatures: vec![],
+            },
+            mode: SyncMode::Sync,
+        };
+
+        let url = mock_server(
+            "200 OK",
+            r#"{"height":"0","txhash":"BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D","raw_log":"[]"}"#,
+        )
+        .await;
+        let result = client(url).broadcast_tx(tx.clone()).await.unwrap();
+        assert!(result.is_ok());
+        assert_eq!(
+            result.hash,
+            "BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D"
+        );
+
+        let url = mock_server(
+            "200 OK",
+            r#"{"height":"0","txhash":"BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D","codespace":"sdk","code":32,"raw_log":"account sequence mismatch, expected 25, got 24: incorrect account sequence"}"#,
+        )
+        .await;
+        let error = client(url).broadcast_tx(tx.clone()).await.unwrap_err();
+        assert_eq!(
+            error.sdk_error(),
+            Some(SdkError::WrongSequence {
+                expected: Some(25),
+                got: Some(24)
+            })
+        );
+        match error {
+            Error::NodeError {
+                code,
+                codespace,
+                raw_log,
+            } => {
+                assert_eq!(code, 32);
+                assert_eq!(codespace, "sdk");
+                assert!(raw_log.starts_with("account sequence mismatch"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let url = mock_server(
+            "400 Bad Request",
+            r#"{"error":"tx parse error: unknown field"}"#,
+        )
+        .await;
+        match client(url).broadcast_tx(tx.clone()).await {
+            Err(Error::NodeError { raw_log, .. }) => {
+                assert_eq!(raw_log, "tx parse error: unknown field")
+            }
+            other => panic!("unexpected result: {:?}", other),
         }
-        let response = response.json::<serde_json::Value>().await?;
-        let tx_hash = response["txhash"].as_str().unwrap();
-        Ok(tx_hash.into())
+
+        let url = mock_server("500 Internal Server Error", "internal error").await;
+        let result = client(url).broadcast_tx(tx).await;
+        assert!(matches!(result, Err(Error::ClientError(_))));
+    }
+
+    #[cfg(feature = "grpc")]
+    #[test]
+    fn test_status_error() {
+        use crate::types::broadcast::SdkError;
+
+        let error = status_error(tonic::Status::unknown("tx already exists in cache"));
+        assert_eq!(error.sdk_error(), Some(SdkError::TxInMempoolCache));
+        let error = status_error(tonic::Status::unavailable("connection refused"));
+        assert!(matches!(error, Error::TransportError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_tx_rest() {
+        let (url, mut requests) = mock_server_sequence(vec![(
+            "200 OK",
+            r#"{"tx_response":{"height":"0","txhash":"BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D","codespace":"","code":0,"raw_log":"[]","logs":[],"gas_wanted":"0","gas_used":"0"}}"#,
+        )])
+        .await;
+        let result = client(url)
+            .broadcast_tx_rest(&[1, 2, 3], SyncMode::Async)
+            .await
+            .unwrap();
+        assert!(result.is_ok());
+        assert_eq!(
+            result.hash,
+            "BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D"
+        );
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with("POST /cosmos/tx/v1beta1/txs "));
+        assert!(request.ends_with(r#"{"mode":"BROADCAST_MODE_ASYNC","tx_bytes":"AQID"}"#));
+
+        let url = mock_server(
+            "400 Bad Request",
+            r#"{"code":3,"message":"invalid empty tx","details":[]}"#,
+        )
+        .await;
+        let result = client(url).broadcast_tx_rest(&[], SyncMode::Sync).await;
+        assert!(matches!(result, Err(Error::NodeError { code: 3, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_tx() {
+        const HASH: &str = "BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D";
+        const NOT_FOUND: (&str, &str) = (
+            "404 Not Found",
+            r#"{"code":5,"message":"rpc error: code = NotFound desc = tx not found: BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D","details":[]}"#,
+        );
+
+        let (url, mut requests) = mock_server_sequence(vec![
+            NOT_FOUND,
+            (
+                "200 OK",
+                r#"{"tx":{},"tx_response":{"height":"1024","txhash":"BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D","code":0,"raw_log":"[]","gas_wanted":"200000","gas_used":"62545","timestamp":"2022-01-10T08:01:23Z"}}"#,
+            ),
+        ])
+        .await;
+        let result = client(url)
+            .wait_for_tx(HASH, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(result.height, 1024);
+        assert_eq!(result.gas_used, 62545);
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with(&format!("GET /cosmos/tx/v1beta1/txs/{} ", HASH)));
+
+        let (url, _) = mock_server_sequence(vec![NOT_FOUND, NOT_FOUND]).await;
+        let result = client(url)
+            .wait_for_tx(HASH, Duration::from_millis(100))
+            .await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+
+        let (url, _) = mock_server_sequence(vec![
+            ("200 OK", r#"{"block":{"header":{"height":"100"}}}"#),
+            NOT_FOUND,
+        ])
+        .await;
+        let result = client(url)
+            .wait_for_tx_until_height(HASH, Duration::from_secs(10), 100)
+            .await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_balances() {
+        let (url, mut requests) = mock_server_sequence(vec![
+            (
+                "200 OK",
+                r#"{"balances":[{"denom":"basecro","amount":"100000000"}],"pagination":{"next_key":"AAE=","total":"0"}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"balances":[{"denom":"ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2","amount":"1200000"}],"pagination":{"next_key":null,"total":"0"}}"#,
+            ),
+        ])
+        .await;
+        let balances = client(url)
+            .get_balances("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .await
+            .unwrap();
+        assert_eq!(balances.amount_of("basecro"), 100_000_000);
+        assert_eq!(balances.iter().count(), 2);
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with(
+            "GET /cosmos/bank/v1beta1/balances/cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf?pagination.limit=100 "
+        ));
+        let request = requests.recv().await.unwrap();
+        assert!(request.contains("pagination.key=AAE%3D"));
+
+        let url = mock_server(
+            "200 OK",
+            r#"{"metadata":{"description":"The native token of Crypto.org Chain","denom_units":[{"denom":"basecro","exponent":0,"aliases":[]},{"denom":"cro","exponent":8,"aliases":[]}],"base":"basecro","display":"cro","name":"","symbol":""}}"#,
+        )
+        .await;
+        let metadata = client(url).get_denom_metadata("basecro").await.unwrap();
+        assert_eq!(metadata, DenomMetadata::new("basecro", "cro", "CRO", 8));
+
+        let url = mock_server("200 OK", r#"{"balance":{"denom":"basecro","amount":"0"}}"#).await;
+        let balance = client(url)
+            .get_balance("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf", "basecro")
+            .await
+            .unwrap();
+        assert!(balance.is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_ibc_denom_paths() {
+        const IBC: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+        let (url, mut requests) = mock_server_sequence(vec![
+            (
+                "200 OK",
+                r#"{"balance":{"denom":"ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2","amount":"1200000"}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"supply":[{"denom":"basecro","amount":"3000000000000000000"},{"denom":"ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2","amount":"1200000"}],"pagination":{"next_key":null,"total":"2"}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"metadatas":[{"description":"","denom_units":[{"denom":"basecro","exponent":0,"aliases":[]},{"denom":"cro","exponent":8,"aliases":[]}],"base":"basecro","display":"cro","name":"","symbol":""}],"pagination":{"next_key":null,"total":"1"}}"#,
+            ),
+        ])
+        .await;
+        let client = client(url);
+        let balance = client
+            .get_balance("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf", IBC)
+            .await
+            .unwrap();
+        assert_eq!(balance.denom(), IBC);
+        assert!(requests.recv().await.unwrap().starts_with(
+            "GET /cosmos/bank/v1beta1/balances/cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf/by_denom?denom=ibc%2F27394FB0"
+        ));
+        let supply = client.get_supply_of(IBC).await.unwrap();
+        assert_eq!(supply.amount(), 1200000);
+        assert!(requests
+            .recv()
+            .await
+            .unwrap()
+            .starts_with("GET /cosmos/bank/v1beta1/supply?"));
+        // no metadata registered for the denomination
+        let result = client.get_denom_metadata(IBC).await;
+        assert!(matches!(result, Err(Error::ClientError(_))));
+        assert!(requests
+            .recv()
+            .await
+            .unwrap()
+            .starts_with("GET /cosmos/bank/v1beta1/denoms_metadata?"));
     }
 }
@@ -0,0 +1,448 @@
+use super::rpc::RpcClient;
+use super::{json_u64, node_error, read_json};
+use crate::error::Error;
+#[cfg(feature = "grpc")]
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(feature = "grpc")]
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::time::Duration;
+#[cfg(feature = "grpc")]
+use tonic::transport::Channel;
+
+/// default number of retries of a request failing with a transport error
+pub const DEFAULT_MAX_RETRIES: usize = 3;
+
+/// default delay before the first retry, it doubles with each further retry
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// the delay between retries doubles up to this limit
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(5);
+
+/// default number of blocks an endpoint may lag behind the highest one and stay healthy
+pub const DEFAULT_MAX_HEIGHT_LAG: u64 = 5;
+
+/// urls of a full node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    /// REST api url, `api.address` in $CHAIN_MAIND_HOME/config/app.toml
+    pub api_url: String,
+    /// Tendermint gRPC url, `rpc.grpc_laddr` in $CHAIN_MAIND_HOME/config/config.toml
+    #[cfg(feature = "grpc")]
+    pub grpc_url: String,
+    /// cosmos gRPC url, `grpc.address` in $CHAIN_MAIND_HOME/config/app.toml
+    #[cfg(feature = "grpc")]
+    pub cosmos_grpc_url: Option<String>,
+    /// Tendermint RPC url, `rpc.laddr` in $CHAIN_MAIND_HOME/config/config.toml,
+    /// the node status is queried from it in health checks if set
+    pub rpc_url: Option<String>,
+}
+
+impl Endpoint {
+    /// create a new endpoint
+    #[cfg(not(feature = "grpc"))]
+    pub fn new(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            rpc_url: None,
+        }
+    }
+
+    #[cfg(feature = "grpc")]
+    pub fn new(api_url: impl Into<String>, grpc_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            grpc_url: grpc_url.into(),
+            cosmos_grpc_url: None,
+            rpc_url: None,
+        }
+    }
+
+    /// set the cosmos gRPC url used for module queries
+    #[cfg(feature = "grpc")]
+    pub fn set_cosmos_grpc_url(&mut self, cosmos_grpc_url: impl Into<String>) -> &mut Self {
+        self.cosmos_grpc_url = Some(cosmos_grpc_url.into());
+        self
+    }
+
+    /// set the Tendermint RPC url
+    pub fn set_rpc_url(&mut self, rpc_url: impl Into<String>) -> &mut Self {
+        self.rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    /// latest block height and whether the node is catching up
+    async fn status(&self, http: &reqwest::Client) -> Result<(u64, bool), Error> {
+        if let Some(rpc_url) = &self.rpc_url {
+            let status = RpcClient::new(rpc_url)
+                .set_http_client(http.clone())
+                .status()
+                .await?;
+            return Ok((
+                status.sync_info.latest_block_height,
+                status.sync_info.catching_up,
+            ));
+        }
+        let get = |path: &str| {
+            let request = http.get(format!("{}{}", self.api_url, path));
+            async move {
+                let (status, response) = read_json(request.send().await?).await?;
+                if !status.is_success() {
+                    return Err(node_error(&response));
+                }
+                Ok(response)
+            }
+        };
+        // {'syncing': false}
+        let syncing = get("/cosmos/base/tendermint/v1beta1/syncing").await?["syncing"]
+            .as_bool()
+            .unwrap_or_default();
+        let block = get("/cosmos/base/tendermint/v1beta1/blocks/latest").await?;
+        let height = json_u64(&block["block"]["header"]["height"], "block height")?;
+        Ok((height, syncing))
+    }
+}
+
+/// outcome of the health check of an endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// REST api url of the endpoint
+    pub api_url: String,
+    /// latest block height, `None` if the node is unreachable
+    pub height: Option<u64>,
+    /// whether the node is still syncing
+    pub catching_up: bool,
+    /// whether requests are sent to the endpoint
+    pub healthy: bool,
+}
+
+/// endpoints in order of preference, whether they are healthy and their gRPC channels,
+/// shared by client clones
+#[derive(Debug)]
+pub(super) struct EndpointPool {
+    endpoints: RwLock<Vec<Endpoint>>,
+    healthy: Vec<AtomicBool>,
+    next: AtomicUsize,
+    #[cfg(feature = "grpc")]
+    channels: Mutex<HashMap<String, Channel>>,
+    #[cfg(feature = "grpc")]
+    grpc_timeout: Mutex<Option<Duration>>,
+}
+
+impl EndpointPool {
+    /// pool of at least one endpoint
+    pub(super) fn new(endpoints: Vec<Endpoint>) -> Self {
+        Self {
+            healthy: endpoints.iter().map(|_| AtomicBool::new(true)).collect(),
+            endpoints: RwLock::new(endpoints),
+            next: AtomicUsize::new(0),
+            #[cfg(feature = "grpc")]
+            channels: Mutex::new(HashMap::new()),
+            #[cfg(feature = "grpc")]
+            grpc_timeout: Mutex::new(None),
+        }
+    }
+
+    pub(super) fn endpoints(&self) -> Vec<Endpoint> {
+        self.endpoints.read().expect("lock endpoints").clone()
+    }
+
+    pub(super) fn endpoint(&self, index: usize) -> Endpoint {
+        self.endpoints.read().expect("lock endpoints")[index].clone()
+    }
+
+    /// set the cosmos gRPC url of the endpoint at `index`
+    #[cfg(feature = "grpc")]
+    pub(super) fn set_cosmos_grpc_url(&self, index: usize, cosmos_grpc_url: String) {
+        self.endpoints.write().expect("lock endpoints")[index].cosmos_grpc_url =
+            Some(cosmos_grpc_url);
+    }
+
+    #[cfg(feature = "grpc")]
+    pub(super) fn grpc_timeout(&self) -> Option<Duration> {
+        *self.grpc_timeout.lock().expect("lock grpc timeout")
+    }
+
+    /// set the timeout of gRPC requests, the channels are connected again with it
+    #[cfg(feature = "grpc")]
+    pub(super) fn set_grpc_timeout(&self, timeout: Duration) {
+        *self.grpc_timeout.lock().expect("lock grpc timeout") = Some(timeout);
+        self.channels.lock().expect("lock channels").clear();
+    }
+
+    /// index of the endpoint for the `attempt`-th try of a request among the `usable` ones,
+    /// the first healthy one, or the next healthy one in turn with `load_balancing`;
+    /// `None` if no endpoint is usable
+    pub(super) fn pick(
+        &self,
+        load_balancing: bool,
+        attempt: usize,
+        usable: impl Fn(&Endpoint) -> bool,
+    ) -> Option<usize> {
+        let endpoints = self.endpoints.read().expect("lock endpoints");
+        let count = endpoints.len();
+        let start = if load_balancing {
+            self.next.fetch_add(1, Ordering::Relaxed) % count
+        } else {
+            0
+        };
+        let candidates: Vec<usize> = (0..count)
+            .map(|i| (start + i) % count)
+            .filter(|i| usable(&endpoints[*i]))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let healthy = candidates
+            .iter()
+            .find(|i| self.healthy[**i].load(Ordering::Relaxed));
+        // all endpoints failed, try them in turn until a health check succeeds
+        Some(*healthy.unwrap_or(&candidates[attempt % candidates.len()]))
+    }
+
+    pub(super) fn set_healthy(&self, index: usize, healthy: bool) {
+        self.healthy[index].store(healthy, Ordering::Relaxed);
+    }
+
+    /// channel connected to the gRPC server at `url` before
+    #[cfg(feature = "grpc")]
+    pub(super) fn channel(&self, url: &str) -> Option<Channel> {
+        self.channels
+            .lock()
+            .expect("lock channels")
+            .get(url)
+            .cloned()
+    }
+
+    /// keep a channel to reuse it for all further requests, it reconnects by itself
+    #[cfg(feature = "grpc")]
+    pub(super) fn add_channel(&self, url: String, channel: Channel) {
+        self.channels
+            .lock()
+            .expect("lock channels")
+            .insert(url, channel);
+    }
+
+    /// check all endpoints and only use the reachable and synced ones
+    /// at most `max_height_lag` blocks behind the highest one
+    pub(super) async fn check_health(
+        &self,
+        http: &reqwest::Client,
+        max_height_lag: u64,
+    ) -> Vec<EndpointHealth> {
+        let endpoints = self.endpoints();
+        let statuses = endpoints.iter().map(|endpoint| endpoint.status(http));
+        let statuses = futures::future::join_all(statuses).await;
+        let max_height = statuses
+            .iter()
+            .filter_map(|status| status.as_ref().ok().map(|(height, _)| *height))
+            .max()
+            .unwrap_or_default();
+        endpoints
+            .iter()
+            .zip(statuses)
+            .enumerate()
+            .map(|(index, (endpoint, status))| {
+                let (height, catching_up) = match status {
+                    Ok((height, catching_up)) => (Some(height), catching_up),
+                    Err(e) => {
+                        log::warn!("endpoint {} is unreachable: {}", endpoint.api_url, e);
+                        (None, false)
+                    }
+                };
+                let healthy = !catching_up
+                    && matches!(height, Some(height) if height + max_height_lag >= max_height);
+                self.set_healthy(index, healthy);
+                EndpointHealth {
+                    api_url: endpoint.api_url.clone(),
+                    height,
+                    catching_up,
+                    healthy,
+                }
+            })
+            .collect()
+    }
+}
+
+/// whether a request failed before reaching the node, or without an answer
+pub(super) fn is_transport_error(error: &Error) -> bool {
+    matches!(error, Error::TransportError(_))
+}
+
+/// delay before the retry following one after `delay`
+pub(super) fn next_backoff(delay: Duration) -> Duration {
+    (delay * 2).min(MAX_RETRY_BACKOFF)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::mock::{mock_server, mock_server_sequence};
+    use super::super::Client;
+    use super::*;
+    use crate::types::basic::SyncMode;
+
+    #[cfg(not(feature = "grpc"))]
+    fn endpoint(api_url: String) -> Endpoint {
+        Endpoint::new(api_url)
+    }
+
+    #[cfg(feature = "grpc")]
+    fn endpoint(api_url: String) -> Endpoint {
+        Endpoint::new(api_url, "http://127.0.0.1:1")
+    }
+
+    fn client(api_urls: Vec<String>) -> Client {
+        let mut client =
+            Client::with_endpoints(api_urls.into_iter().map(endpoint).collect()).unwrap();
+        client.set_retry_backoff(Duration::from_millis(1));
+        client
+    }
+
+    #[tokio::test]
+    async fn test_failover() {
+        assert!(Client::with_endpoints(vec![]).is_err());
+        let url = mock_server(
+            "200 OK",
+            r#"{"account":{"@type":"/cosmos.auth.v1beta1.BaseAccount","address":"cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf","account_number":"9","sequence":"25"}}"#,
+        )
+        .await;
+        let client = client(vec!["http://127.0.0.1:1".to_string(), url.clone()]);
+        let info = client
+            .get_account_info("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .await
+            .unwrap();
+        assert_eq!(info, (9, 25));
+        // the unreachable endpoint is skipped until it is healthy again
+        assert_eq!(client.pool.pick(false, 0, |_| true), Some(1));
+
+        let mut client = client.clone();
+        client.set_max_retries(0);
+        client.pool.set_healthy(1, true);
+        client.pool.set_healthy(0, true);
+        let result = client.latest_height().await;
+        assert!(matches!(result, Err(Error::TransportError(_))));
+        // the last attempt marks the endpoint unhealthy as well
+        assert!(!client.pool.healthy[0].load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_retry_is_idempotent() {
+        let (url, mut requests) = mock_server_sequence(vec![
+            (
+                "404 Not Found",
+                r#"{"code":5,"message":"rpc error: code = NotFound desc = tx not found","details":[]}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"tx_response":{"height":"0","txhash":"039058C6F2C0CB492C533B0A4D14EF77CC0F78ABCCCED5287D84A1A2011CFB81","codespace":"sdk","code":19,"raw_log":"tx already exists in cache"}}"#,
+            ),
+        ])
+        .await;
+        let client = client(vec!["http://127.0.0.1:1".to_string(), url]);
+        let result = client
+            .broadcast_tx_rest(&[1, 2, 3], SyncMode::Sync)
+            .await
+            .unwrap();
+        assert_eq!(result.code, 0);
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with(
+            "GET /cosmos/tx/v1beta1/txs/039058C6F2C0CB492C533B0A4D14EF77CC0F78ABCCCED5287D84A1A2011CFB81"
+        ));
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with("POST /cosmos/tx/v1beta1/txs"));
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    #[tokio::test]
+    async fn test_amino_broadcast_retry_is_idempotent() {
+        use crate::types::basic::Fee;
+        use crate::types::signature::Signature;
+        use crate::types::transaction::{Transaction, Tx};
+
+        let signature: Signature = serde_json::from_str(
+            r#"{"signature":"c2lnbmF0dXJl","pub_key":{"type":"tendermint/PubKeySecp256k1","value":"A+2sgzkhGZ7IOP3ApCYgNSzYZ8Z+Y7cfRnJ/XjWKgYRj"}}"#,
+        )
+        .unwrap();
+        let tx: Transaction<serde_json::Value> = Transaction {
+            tx: Tx {
+                messages: vec![],
+                fee: Fee::default(),
+                memo: String::new(),
+                signatures: vec![signature],
+            },
+            mode: SyncMode::Sync,
+        };
+        let (url, mut requests) = mock_server_sequence(vec![
+            (
+                "200 OK",
+                r#"{"txs":[],"tx_responses":[],"pagination":{"next_key":null,"total":"0"}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"height":"0","txhash":"BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D","codespace":"sdk","code":19,"raw_log":"tx already exists in cache"}"#,
+            ),
+        ])
+        .await;
+        let client = client(vec!["http://127.0.0.1:1".to_string(), url]);
+        let result = client.broadcast_tx(tx).await.unwrap();
+        assert_eq!(result.code, 0);
+        let request = requests.recv().await.unwrap();
+        assert!(request
+            .starts_with("GET /cosmos/tx/v1beta1/txs?events=tx.signature%3D%27c2lnbmF0dXJl%27 "));
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with("POST /txs "));
+    }
+
+    #[tokio::test]
+    async fn test_check_health() {
+        let (synced, _) = mock_server_sequence(vec![
+            ("200 OK", r#"{"syncing":false}"#),
+            (
+                "200 OK",
+                r#"{"block":{"header":{"chain_id":"chainmain-1","height":"1024"}}}"#,
+            ),
+        ])
+        .await;
+        let (lagging, _) = mock_server_sequence(vec![
+            ("200 OK", r#"{"syncing":false}"#),
+            (
+                "200 OK",
+                r#"{"block":{"header":{"chain_id":"chainmain-1","height":"1000"}}}"#,
+            ),
+        ])
+        .await;
+        let client = client(vec![
+            lagging.clone(),
+            synced.clone(),
+            "http://127.0.0.1:1".to_string(),
+        ]);
+        let health = client.check_health().await;
+        assert_eq!(health[0].height, Some(1000));
+        assert!(!health[0].healthy);
+        assert_eq!(health[1].height, Some(1024));
+        assert!(health[1].healthy);
+        assert_eq!(health[2].height, None);
+        assert!(!health[2].healthy);
+        assert_eq!(client.pool.pick(false, 0, |_| true), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_health_checks() {
+        let (url, _) = mock_server_sequence(vec![
+            ("200 OK", r#"{"syncing":false}"#),
+            (
+                "200 OK",
+                r#"{"block":{"header":{"chain_id":"chainmain-1","height":"1024"}}}"#,
+            ),
+        ])
+        .await;
+        let client = client(vec![url]);
+        client.pool.set_healthy(0, false);
+        // the first check runs right away, the next one only after the test
+        let checks = client.clone().spawn_health_checks(Duration::from_secs(60));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(client.pool.healthy[0].load(Ordering::Relaxed));
+        checks.abort();
+    }
+}
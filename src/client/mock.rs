@@ -0,0 +1,83 @@
+#[cfg(feature = "grpc")]
+use futures::stream::{self, Stream};
+#[cfg(feature = "grpc")]
+use std::pin::Pin;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+#[cfg(feature = "grpc")]
+use tokio::net::TcpStream;
+
+/// connections accepted by a gRPC test server
+#[cfg(feature = "grpc")]
+pub(crate) type Incoming = Pin<Box<dyn Stream<Item = std::io::Result<TcpStream>> + Send>>;
+
+/// serve a single HTTP response on a random local port, returns the base url
+pub(crate) async fn mock_server(status: &'static str, body: &'static str) -> String {
+    mock_server_sequence(vec![(status, body)]).await.0
+}
+
+/// serve one HTTP response per connection in order on a random local port,
+/// returns the base url and the received requests
+pub(crate) async fn mock_server_sequence(
+    responses: Vec<(&'static str, &'static str)>,
+) -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        for (status, body) in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut socket).await;
+            let response = format!(
+                "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            let _ = sender.send(request);
+        }
+    });
+    (format!("http://{}", addr), receiver)
+}
+
+/// listen on a random local port for a gRPC test server, e.g. served with
+/// `tonic::transport::Server::serve_with_incoming`, returns the url and the connections
+#[cfg(feature = "grpc")]
+pub(crate) async fn mock_grpc_incoming() -> (String, Incoming) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = stream::unfold(listener, |listener| async move {
+        let socket = listener.accept().await.map(|(socket, _)| socket);
+        Some((socket, listener))
+    });
+    (format!("http://{}", addr), Box::pin(incoming))
+}
+
+/// read until the headers and the announced body are received
+async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+    let mut request = vec![];
+    let mut buf = [0; 4096];
+    loop {
+        let n = socket.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request).to_string();
+        let complete = match text.find("\r\n\r\n") {
+            Some(end) => {
+                let content_length = text[..end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or_default();
+                request.len() >= end + 4 + content_length
+            }
+            None => false,
+        };
+        if n == 0 || complete {
+            return text;
+        }
+    }
+}
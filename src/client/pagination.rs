@@ -0,0 +1,339 @@
+use super::{node_error, Client};
+use crate::error::Error;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::base::query::v1beta1::{PageRequest, PageResponse};
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// default number of items per page
+pub const DEFAULT_PAGE_LIMIT: u64 = 100;
+
+/// options of a paginated list query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    limit: u64,
+    reverse: bool,
+    count_total: bool,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_PAGE_LIMIT,
+            reverse: false,
+            count_total: false,
+        }
+    }
+}
+
+impl Pagination {
+    /// set the number of items per page
+    pub fn set_limit(&mut self, limit: u64) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+
+    /// list items in descending order
+    pub fn set_reverse(&mut self, reverse: bool) -> &mut Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// query the total number of items with the first page, see `PageStream::total`
+    pub fn set_count_total(&mut self, count_total: bool) -> &mut Self {
+        self.count_total = count_total;
+        self
+    }
+}
+
+/// request of one page, `key` is empty for the first one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageQuery {
+    /// `next_key` of the previous page
+    pub key: Vec<u8>,
+    /// number of items per page
+    pub limit: u64,
+    /// list items in descending order
+    pub reverse: bool,
+    /// query the total number of items
+    pub count_total: bool,
+}
+
+impl PageQuery {
+    /// `pagination.*` parameters of a REST list query
+    pub fn rest_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("pagination.limit", self.limit.to_string())];
+        if !self.key.is_empty() {
+            query.push(("pagination.key", base64::encode(&self.key)));
+        }
+        if self.reverse {
+            query.push(("pagination.reverse", "true".to_string()));
+        }
+        if self.count_total {
+            query.push(("pagination.count_total", "true".to_string()));
+        }
+        query
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<PageQuery> for PageRequest {
+    fn from(query: PageQuery) -> Self {
+        Self {
+            key: query.key,
+            limit: query.limit,
+            reverse: query.reverse,
+            count_total: query.count_total,
+            ..Default::default()
+        }
+    }
+}
+
+/// items of one page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// items of the page
+    pub items: Vec<T>,
+    /// key of the next page, empty for the last one
+    pub next_key: Vec<u8>,
+    /// total number of items if requested with `count_total`
+    pub total: u64,
+}
+
+impl<T: DeserializeOwned> Page<T> {
+    /// page of a REST list response, `items` is the name of the list
+    pub fn from_json(response: &serde_json::Value, items: &str) -> Result<Self, Error> {
+        // {'balances': [...], 'pagination': {'next_key': 'AA==', 'total': '0'}}
+        let invalid = |e: String| Error::ClientError(format!("invalid page {}: {}", response, e));
+        let list = match &response[items] {
+            serde_json::Value::Null => vec![],
+            list => serde_json::from_value(list.clone()).map_err(|e| invalid(e.to_string()))?,
+        };
+        let next_key = match response["pagination"]["next_key"].as_str() {
+            Some(key) => base64::decode(key).map_err(|e| invalid(e.to_string()))?,
+            None => vec![],
+        };
+        let total = response["pagination"]["total"]
+            .as_str()
+            .and_then(|total| total.parse().ok())
+            .unwrap_or_default();
+        Ok(Self {
+            items: list,
+            next_key,
+            total,
+        })
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl<T> Page<T> {
+    /// page of a gRPC list response
+    pub fn from_grpc(items: Vec<T>, page: Option<PageResponse>) -> Self {
+        let page = page.unwrap_or_default();
+        Self {
+            items,
+            next_key: page.next_key,
+            total: page.total,
+        }
+    }
+}
+
+/// stream of the items of all pages of a list query,
+/// a page is only queried once the items of the previous one are consumed
+pub struct PageStream<T> {
+    inner: Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>,
+    total: Arc<Mutex<Option<u64>>>,
+}
+
+impl<T> PageStream<T> {
+    /// stream of a single error, e.g. for invalid query arguments
+    pub fn error(error: Error) -> Self
+    where
+        T: Send + 'static,
+    {
+        Self {
+            inner: Box::pin(stream::once(async { Err(error) })),
+            total: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// total number of items, known after the first page if requested with `count_total`
+    pub fn total(&self) -> Option<u64> {
+        *self.total.lock().expect("lock total")
+    }
+}
+
+impl<T> Stream for PageStream<T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+/// stream the items of a list query following `next_key`, `fetch` queries one page
+pub fn paginate<T, F, Fut>(pagination: Pagination, fetch: F) -> PageStream<T>
+where
+    T: Send + 'static,
+    F: FnMut(PageQuery) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Page<T>, Error>> + Send + 'static,
+{
+    let total = Arc::new(Mutex::new(None));
+    let page_total = total.clone();
+    let pages = stream::try_unfold((fetch, Some(Vec::new())), move |(mut fetch, next_key)| {
+        let pagination = pagination.clone();
+        let total = page_total.clone();
+        async move {
+            let key = match next_key {
+                Some(key) => key,
+                None => return Ok::<_, Error>(None),
+            };
+            let first = key.is_empty();
+            let query = PageQuery {
+                key,
+                limit: pagination.limit,
+                reverse: pagination.reverse,
+                count_total: pagination.count_total && first,
+            };
+            let page = fetch(query).await?;
+            if first && pagination.count_total {
+                *total.lock().expect("lock total") = Some(page.total);
+            }
+            let next_key = Some(page.next_key).filter(|key| !key.is_empty());
+            Ok(Some((
+                stream::iter(page.items.into_iter().map(Ok)),
+                (fetch, next_key),
+            )))
+        }
+    });
+    PageStream {
+        inner: Box::pin(pages.try_flatten()),
+        total,
+    }
+}
+
+impl Client {
+    /// stream the items of a REST list query, e.g. path "/cosmos/bank/v1beta1/supply"
+    /// with the list `items` "supply"
+    pub fn paginate_rest<T>(&self, path: &str, items: &str, pagination: Pagination) -> PageStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let client = self.clone();
+        let path = path.to_string();
+        let items = items.to_string();
+        paginate(pagination, move |query| {
+            let client = client.clone();
+            let path = path.clone();
+            let items = items.clone();
+            async move {
+                let (status, response) = client.rest_get(&path, &query.rest_query()).await?;
+                if !status.is_success() {
+                    return Err(node_error(&response));
+                }
+                Page::from_json(&response, &items)
+            }
+        })
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl Client {
+    /// stream the items of a gRPC list query, e.g. `request` building a `QueryValidatorsRequest`
+    /// for a page, `call` sending it on a channel and the list `items`
+    /// `|response| (response.validators, response.pagination)`
+    pub fn paginate_grpc<T, Req, Resp, B, C, Fut, I>(
+        &self,
+        pagination: Pagination,
+        request: B,
+        call: C,
+        items: I,
+    ) -> PageStream<T>
+    where
+        T: Send + 'static,
+        Req: Clone + Send + Sync + 'static,
+        B: Fn(PageRequest) -> Req + Send + 'static,
+        C: Fn(tonic::transport::Channel, Req) -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = Result<tonic::Response<Resp>, tonic::Status>> + Send + 'static,
+        I: Fn(Resp) -> (Vec<T>, Option<PageResponse>) + Clone + Send + 'static,
+    {
+        let client = self.clone();
+        paginate(pagination, move |query| {
+            let client = client.clone();
+            let request = request(query.into());
+            let (call, items) = (call.clone(), items.clone());
+            async move {
+                let response = client.cosmos_grpc(request, call).await?;
+                let (list, page) = items(response);
+                Ok(Page::from_grpc(list, page))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_paginate() {
+        let mut pagination = Pagination::default();
+        pagination.set_limit(2).set_count_total(true);
+        let mut queries = vec![];
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut items = paginate(pagination, move |query: PageQuery| {
+            sender.send(query.clone()).unwrap();
+            async move {
+                let start = query.key.first().copied().unwrap_or_default() as u64;
+                let next_key = if start < 4 {
+                    vec![start as u8 + 2]
+                } else {
+                    vec![]
+                };
+                Ok(Page {
+                    items: vec![start, start + 1],
+                    next_key,
+                    total: if query.count_total { 6 } else { 0 },
+                })
+            }
+        });
+        assert_eq!(items.total(), None);
+        assert_eq!(items.next().await.unwrap().unwrap(), 0);
+        assert_eq!(items.total(), Some(6));
+        // pages are only queried on demand
+        queries.push(receiver.recv().await.unwrap());
+        assert!(receiver.try_recv().is_err());
+
+        let rest: Vec<u64> = items.try_collect().await.unwrap();
+        assert_eq!(rest, vec![1, 2, 3, 4, 5]);
+        while let Ok(query) = receiver.try_recv() {
+            queries.push(query);
+        }
+        assert_eq!(queries.len(), 3);
+        assert!(queries[0].count_total);
+        assert_eq!(queries[1].key, vec![2]);
+        assert!(!queries[1].count_total);
+        assert_eq!(
+            queries[2].rest_query(),
+            vec![
+                ("pagination.limit", "2".to_string()),
+                ("pagination.key", "BA==".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_page_stream_error() {
+        let mut items: PageStream<u64> = PageStream::error(Error::InputError("invalid".into()));
+        assert!(matches!(
+            items.next().await,
+            Some(Err(Error::InputError(_)))
+        ));
+        assert!(items.next().await.is_none());
+    }
+}
@@ -0,0 +1,276 @@
+use super::read_json;
+use crate::error::Error;
+use crate::types::broadcast::BroadcastResult;
+use crate::types::tendermint::{
+    AbciQuery, BlockResponse, BlockResults, BroadcastTxCommitResponse, BroadcastTxResponse,
+    NetInfo, Order, Status, TxInfo, TxSearch, Validators,
+};
+use bitcoin_hashes::hex::{FromHex, ToHex};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// maximum page size of `tx_search` and `validators`
+pub const MAX_PER_PAGE: u8 = 100;
+
+/// JSON-RPC error of a response, e.g. an unknown height or a cancelled subscription
+pub(super) fn rpc_error(response: &Value) -> Option<Error> {
+    // {'jsonrpc': '2.0', 'id': 0, 'error': {'code': -32603, 'message': 'Internal error', 'data': 'height 100 must be less than or equal to the current blockchain height 50'}}
+    let error = response.get("error")?;
+    Some(Error::ClientError(format!(
+        "rpc error {}: {} {}",
+        error["code"],
+        error["message"].as_str().unwrap_or_default(),
+        error["data"].as_str().unwrap_or_default()
+    )))
+}
+
+/// client of the Tendermint JSON-RPC API, `rpc.laddr` in config.toml
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    url: String,
+    client: reqwest::Client,
+    next_id: Arc<AtomicU64>,
+}
+
+impl RpcClient {
+    /// create a new client, e.g. with "http://127.0.0.1:26657"
+    pub fn new(rpc_url: &str) -> Self {
+        Self {
+            url: rpc_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// set the HTTP client, e.g. with timeouts, TLS roots, a proxy or default headers
+    pub fn set_http_client(&mut self, client: reqwest::Client) -> &mut Self {
+        self.client = client;
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, Error> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let response = self.client.post(&self.url).json(&request).send().await?;
+        let (status, response) = read_json(response).await?;
+        if let Some(error) = rpc_error(&response) {
+            return Err(error);
+        }
+        if !status.is_success() {
+            return Err(Error::ClientError(format!(
+                "invalid response, status: {}, body: {}",
+                status, response
+            )));
+        }
+        serde_json::from_value(response["result"].clone()).map_err(|e| {
+            Error::ClientError(format!("invalid {} response {}: {}", method, response, e))
+        })
+    }
+
+    /// node identity, sync state and validator key
+    pub async fn status(&self) -> Result<Status, Error> {
+        self.call("status", json!({})).await
+    }
+
+    /// connected peers
+    pub async fn net_info(&self) -> Result<NetInfo, Error> {
+        self.call("net_info", json!({})).await
+    }
+
+    /// block at `height`, the latest one if `None`
+    pub async fn block(&self, height: Option<u64>) -> Result<BlockResponse, Error> {
+        self.call("block", height_params(height)).await
+    }
+
+    /// transaction results and begin/end block events at `height`, the latest one if `None`
+    pub async fn block_results(&self, height: Option<u64>) -> Result<BlockResults, Error> {
+        self.call("block_results", height_params(height)).await
+    }
+
+    /// validators at `height`, the latest one if `None`, `page` starts at 1
+    pub async fn validators(
+        &self,
+        height: Option<u64>,
+        page: u32,
+        per_page: u8,
+    ) -> Result<Validators, Error> {
+        let mut params = height_params(height);
+        params["page"] = json!(page.to_string());
+        params["per_page"] = json!(per_page.min(MAX_PER_PAGE).to_string());
+        self.call("validators", params).await
+    }
+
+    /// committed transaction with upper case hex `hash`
+    pub async fn tx(&self, hash: &str) -> Result<TxInfo, Error> {
+        let hash = Vec::<u8>::from_hex(hash)
+            .map_err(|e| Error::InputError(format!("invalid tx hash {}: {}", hash, e)))?;
+        self.call(
+            "tx",
+            json!({ "hash": base64::encode(&hash), "prove": false }),
+        )
+        .await
+    }
+
+    /// one page of transactions matching `query`, e.g. "transfer.recipient='cro1...'",
+    /// `page` starts at 1
+    pub async fn tx_search(
+        &self,
+        query: &str,
+        page: u32,
+        per_page: u8,
+        order: Order,
+    ) -> Result<TxSearch, Error> {
+        self.call(
+            "tx_search",
+            json!({
+                "query": query,
+                "prove": false,
+                "page": page.to_string(),
+                "per_page": per_page.min(MAX_PER_PAGE).to_string(),
+                "order_by": order.as_str(),
+            }),
+        )
+        .await
+    }
+
+    /// all transactions matching `query`, fetched page by page
+    pub async fn tx_search_all(&self, query: &str, order: Order) -> Result<Vec<TxInfo>, Error> {
+        let mut txs = vec![];
+        for page in 1.. {
+            let result = self.tx_search(query, page, MAX_PER_PAGE, order).await?;
+            let last = result.txs.is_empty();
+            txs.extend(result.txs);
+            if last || txs.len() as u64 >= result.total_count {
+                break;
+            }
+        }
+        Ok(txs)
+    }
+
+    /// query the application, e.g. path "/cosmos.bank.v1beta1.Query/Balance" with
+    /// protobuf encoded request `data`, at `height` or the latest one if `None`
+    pub async fn abci_query(
+        &self,
+        path: &str,
+        data: &[u8],
+        height: Option<u64>,
+    ) -> Result<AbciQuery, Error> {
+        let mut params = json!({ "path": path, "data": data.to_hex(), "prove": false });
+        if let Some(height) = height {
+            params["height"] = json!(height.to_string());
+        }
+        let response: Value = self.call("abci_query", params).await?;
+        let query: AbciQuery = serde_json::from_value(response["response"].clone())
+            .map_err(|e| Error::ClientError(format!("invalid abci_query response: {}", e)))?;
+        if query.code != 0 {
+            return Err(Error::NodeError {
+                code: query.code,
+                codespace: query.codespace,
+                raw_log: query.log,
+            });
+        }
+        Ok(query)
+    }
+
+    /// broadcast without waiting for CheckTx, only the hash is set
+    pub async fn broadcast_tx_async(&self, tx: &[u8]) -> Result<BroadcastResult, Error> {
+        let response: BroadcastTxResponse = self.call("broadcast_tx_async", tx_params(tx)).await?;
+        Ok(response.into())
+    }
+
+    /// broadcast and return the CheckTx result,
+    /// the result of a rejected transaction has a non-zero code
+    pub async fn broadcast_tx_sync(&self, tx: &[u8]) -> Result<BroadcastResult, Error> {
+        let response: BroadcastTxResponse = self.call("broadcast_tx_sync", tx_params(tx)).await?;
+        Ok(response.into())
+    }
+
+    /// broadcast and wait until the transaction is committed, only for development,
+    /// the result of a rejected or failed transaction has a non-zero code
+    pub async fn broadcast_tx_commit(&self, tx: &[u8]) -> Result<BroadcastResult, Error> {
+        let response: BroadcastTxCommitResponse =
+            self.call("broadcast_tx_commit", tx_params(tx)).await?;
+        Ok(response.into())
+    }
+}
+
+/// int64 parameters, like `page` and `per_page`, are JSON strings in Tendermint v0.34
+fn height_params(height: Option<u64>) -> Value {
+    let mut params = Map::new();
+    if let Some(height) = height {
+        params.insert("height".to_string(), json!(height.to_string()));
+    }
+    Value::Object(params)
+}
+
+fn tx_params(tx: &[u8]) -> Value {
+    json!({ "tx": base64::encode(tx) })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::mock::{mock_server, mock_server_sequence};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_status() {
+        let url = mock_server(
+            "200 OK",
+            r#"{"jsonrpc":"2.0","id":0,"result":{"node_info":{"protocol_version":{"p2p":"8","block":"11","app":"0"},"id":"f3f0d3a5e1e3a6e6a8d7c0d4e9b3c2a1f0e9d8c7","listen_addr":"tcp://0.0.0.0:26656","network":"chainmain-1","version":"0.34.14","channels":"40202122233038606100","moniker":"node0","other":{"tx_index":"on","rpc_address":"tcp://0.0.0.0:26657"}},"sync_info":{"latest_block_hash":"5D3F2B9A","latest_app_hash":"A1B2C3D4","latest_block_height":"1024","latest_block_time":"2022-01-10T08:01:23.123456Z","earliest_block_hash":"1B2C3D4E","earliest_app_hash":"E3B0C442","earliest_block_height":"1","earliest_block_time":"2022-01-01T00:00:00Z","catching_up":false},"validator_info":{"address":"6E3F3A3E2F0B1C4D","pub_key":{"type":"tendermint/PubKeyEd25519","value":"AQID"},"voting_power":"10"}}}"#,
+        )
+        .await;
+        let status = RpcClient::new(&url).status().await.unwrap();
+        assert_eq!(status.node_info.network, "chainmain-1");
+        assert_eq!(status.sync_info.latest_block_height, 1024);
+        assert!(!status.sync_info.catching_up);
+        assert_eq!(status.validator_info.voting_power, 10);
+    }
+
+    #[tokio::test]
+    async fn test_tx_search_all() {
+        let (url, mut requests) = mock_server_sequence(vec![
+            (
+                "200 OK",
+                r#"{"jsonrpc":"2.0","id":0,"result":{"txs":[{"hash":"BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D","height":"1024","index":0,"tx_result":{"code":0,"data":null,"log":"[]","info":"","gas_wanted":"200000","gas_used":"62545","events":[],"codespace":""},"tx":"AQID"}],"total_count":"2"}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"jsonrpc":"2.0","id":1,"result":{"txs":[{"hash":"0A54C56EEC4A5E0C2E63E95FD0D8F1F3DE9B05A4D4F30B1B2C3D4E5F60718293","height":"1025","index":3,"tx_result":{"code":5,"codespace":"sdk","log":"insufficient funds","gas_wanted":"200000","gas_used":"41000"},"tx":"BAUG"}],"total_count":"2"}}"#,
+            ),
+        ])
+        .await;
+        let txs = RpcClient::new(&url)
+            .tx_search_all("transfer.recipient='cro1'", Order::Asc)
+            .await
+            .unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].tx, vec![1, 2, 3]);
+        assert_eq!(txs[1].height, 1025);
+        assert_eq!(txs[1].result().code, 5);
+        let request = requests.recv().await.unwrap();
+        assert!(request.contains(r#""method":"tx_search""#));
+        assert!(request.contains(r#""page":"1""#));
+        assert!(request.contains(r#""per_page":"100""#));
+        let request = requests.recv().await.unwrap();
+        assert!(request.contains(r#""page":"2""#));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error() {
+        let url = mock_server(
+            "500 Internal Server Error",
+            r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32603,"message":"Internal error","data":"height 2000 must be less than or equal to the current blockchain height 1024"}}"#,
+        )
+        .await;
+        let result = RpcClient::new(&url).block(Some(2000)).await;
+        assert!(
+            matches!(result, Err(Error::ClientError(message)) if message.contains("current blockchain height 1024"))
+        );
+    }
+}
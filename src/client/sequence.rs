@@ -0,0 +1,360 @@
+use super::Client;
+use crate::error::Error;
+use crate::types::broadcast::{BroadcastResult, SdkError};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// default number of re-signs after an "account sequence mismatch"
+pub const DEFAULT_MAX_SEQUENCE_RETRIES: usize = 3;
+
+/// account number and sequence to sign the next transaction of an account with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountSequence {
+    /// account number
+    pub account_number: u64,
+    /// sequence of the next transaction
+    pub sequence: u64,
+}
+
+/// cached sequence of an account and its reserved sequences being broadcasted
+#[derive(Default)]
+struct Cached {
+    sequence: Option<AccountSequence>,
+    in_flight: usize,
+    /// a transaction failed, the sequence is queried again once none is in flight
+    stale: bool,
+}
+
+impl Cached {
+    /// end the broadcast of a reserved sequence
+    fn release(&mut self) {
+        self.in_flight -= 1;
+        if self.in_flight == 0 && self.stale {
+            self.sequence = None;
+            self.stale = false;
+        }
+    }
+}
+
+type Entry = Arc<tokio::sync::Mutex<Cached>>;
+
+/// caches the account number and sequence of sending accounts,
+/// clones share the cache and can be used from several tokio tasks
+#[derive(Clone)]
+pub struct SequenceManager {
+    client: Client,
+    max_retries: usize,
+    accounts: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl SequenceManager {
+    /// create a manager querying unknown accounts through `client`
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            max_retries: DEFAULT_MAX_SEQUENCE_RETRIES,
+            accounts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// set the number of re-signs after an "account sequence mismatch"
+    pub fn set_max_retries(&mut self, max_retries: usize) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    fn entry(&self, address: &str) -> Entry {
+        self.accounts
+            .lock()
+            .expect("lock accounts")
+            .entry(address.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// cached account number and sequence of `address`, queried if unknown
+    pub async fn current(&self, address: &str) -> Result<AccountSequence, Error> {
+        let entry = self.entry(address);
+        let mut cached = entry.lock().await;
+        self.load(address, &mut cached.sequence).await
+    }
+
+    async fn load(
+        &self,
+        address: &str,
+        cached: &mut Option<AccountSequence>,
+    ) -> Result<AccountSequence, Error> {
+        if let Some(current) = *cached {
+            return Ok(current);
+        }
+        let (account_number, sequence) = self.client.get_account_info(address).await?;
+        let current = AccountSequence {
+            account_number,
+            sequence,
+        };
+        *cached = Some(current);
+        Ok(current)
+    }
+
+    /// forget the cached sequence of `address`, it is queried again for the next transaction
+    pub async fn invalidate(&self, address: &str) {
+        self.entry(address).lock().await.sequence = None;
+    }
+
+    /// sign and broadcast a transaction of `address` with its next sequence,
+    /// e.g. by setting it on a `TxBuilder` and calling `Client::broadcast_tx_with_mode`;
+    /// the sequence is reserved before and the lock released while broadcasting, so transactions
+    /// of the same address can be in flight together; after an "account sequence mismatch" the
+    /// sequence is resynchronized and the transaction signed again, after any other error the
+    /// cached sequence is queried again once no other transaction of the address is in flight
+    pub async fn send<F, Fut>(
+        &self,
+        address: &str,
+        mut sign_and_broadcast: F,
+    ) -> Result<BroadcastResult, Error>
+    where
+        F: FnMut(AccountSequence) -> Fut,
+        Fut: Future<Output = Result<BroadcastResult, Error>>,
+    {
+        let entry = self.entry(address);
+        let mut retries = 0;
+        loop {
+            let current = {
+                let mut cached = entry.lock().await;
+                let current = self.load(address, &mut cached.sequence).await?;
+                cached.sequence = Some(AccountSequence {
+                    sequence: current.sequence + 1,
+                    ..current
+                });
+                cached.in_flight += 1;
+                current
+            };
+            let result = sign_and_broadcast(current).await;
+            let mut cached = entry.lock().await;
+            let error = match result {
+                Ok(result) => {
+                    cached.release();
+                    return Ok(result);
+                }
+                Err(error) => error,
+            };
+            match error.sdk_error() {
+                Some(SdkError::WrongSequence { expected, .. }) if retries < self.max_retries => {
+                    retries += 1;
+                    cached.in_flight -= 1;
+                    cached.sequence = match (expected, cached.sequence) {
+                        // later sequences were reserved meanwhile, continue after them
+                        (Some(expected), Some(next)) if next.sequence > expected => Some(next),
+                        (Some(expected), _) => Some(AccountSequence {
+                            sequence: expected,
+                            ..current
+                        }),
+                        (None, _) => None,
+                    };
+                }
+                // rejected in CheckTx the sequence is unused, after a DeliverTx failure or
+                // a transport error it may be used; sequences reserved meanwhile stay cached
+                // until their broadcasts end
+                _ => {
+                    cached.stale = true;
+                    cached.release();
+                    return Err(error);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::mock::{mock_server, mock_server_sequence};
+    use super::*;
+
+    fn manager(url: String) -> SequenceManager {
+        #[cfg(not(feature = "grpc"))]
+        let client = Client::new(url);
+        #[cfg(feature = "grpc")]
+        let client = Client::new(url, "http://127.0.0.1:1".to_string());
+        SequenceManager::new(client)
+    }
+
+    fn result(code: u32, raw_log: &str) -> Result<BroadcastResult, Error> {
+        BroadcastResult {
+            code,
+            codespace: if code == 0 { "" } else { "sdk" }.to_string(),
+            raw_log: raw_log.to_string(),
+            ..Default::default()
+        }
+        .into_result()
+    }
+
+    fn account(sequence: u64) -> &'static str {
+        match sequence {
+            25 => {
+                r#"{"account":{"@type":"/cosmos.auth.v1beta1.BaseAccount","address":"cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf","account_number":"9","sequence":"25"}}"#
+            }
+            30 => {
+                r#"{"account":{"@type":"/cosmos.auth.v1beta1.BaseAccount","address":"cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf","account_number":"9","sequence":"30"}}"#
+            }
+            _ => {
+                r#"{"account":{"@type":"/cosmos.auth.v1beta1.BaseAccount","address":"cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf","account_number":"9","sequence":"31"}}"#
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_concurrently() {
+        let url = mock_server("200 OK", account(25)).await;
+        let manager = manager(url);
+        let address = "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf";
+        // all broadcasts must be in flight together to pass the barrier
+        let barrier = Arc::new(tokio::sync::Barrier::new(10));
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let manager = manager.clone();
+                let barrier = barrier.clone();
+                tokio::spawn(async move {
+                    let mut signed = 0;
+                    let sent = manager
+                        .send(address, |current| {
+                            signed = current.sequence;
+                            let barrier = barrier.clone();
+                            async move {
+                                barrier.wait().await;
+                                result(0, "[]")
+                            }
+                        })
+                        .await
+                        .unwrap();
+                    assert!(sent.is_ok());
+                    signed
+                })
+            })
+            .collect();
+        let mut sequences = vec![];
+        for task in tasks {
+            sequences.push(task.await.unwrap());
+        }
+        sequences.sort_unstable();
+        assert_eq!(sequences, (25..35).collect::<Vec<_>>());
+        let current = manager.current(address).await.unwrap();
+        assert_eq!(current.account_number, 9);
+        assert_eq!(current.sequence, 35);
+    }
+
+    #[tokio::test]
+    async fn test_failure_while_in_flight() {
+        let (url, mut requests) =
+            mock_server_sequence(vec![("200 OK", account(25)), ("200 OK", account(30))]).await;
+        let manager = manager(url);
+        let address = "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf";
+        let account_query = format!("GET /cosmos/auth/v1beta1/accounts/{} ", address);
+        // the two spawned broadcasts stay in flight until the test passes the barrier
+        let barrier = Arc::new(tokio::sync::Barrier::new(3));
+        let (reserved, mut reservations) = tokio::sync::mpsc::unbounded_channel();
+        let tasks: Vec<_> = (0..2)
+            .map(|_| {
+                let manager = manager.clone();
+                let barrier = barrier.clone();
+                let reserved = reserved.clone();
+                tokio::spawn(async move {
+                    let mut signed = 0;
+                    let sent = manager
+                        .send(address, |current| {
+                            signed = current.sequence;
+                            reserved.send(()).unwrap();
+                            let barrier = barrier.clone();
+                            async move {
+                                barrier.wait().await;
+                                result(0, "[]")
+                            }
+                        })
+                        .await
+                        .unwrap();
+                    assert!(sent.is_ok());
+                    signed
+                })
+            })
+            .collect();
+        reservations.recv().await.unwrap();
+        reservations.recv().await.unwrap();
+
+        let mut failed = 0;
+        let sent = manager
+            .send(address, |current| {
+                failed = current.sequence;
+                async { result(5, "insufficient funds") }
+            })
+            .await;
+        assert!(matches!(sent, Err(Error::NodeError { code: 5, .. })));
+        assert_eq!(failed, 27);
+        // the sequences of the broadcasts in flight are not handed out again
+        assert_eq!(manager.current(address).await.unwrap().sequence, 28);
+        assert!(requests.recv().await.unwrap().starts_with(&account_query));
+
+        barrier.wait().await;
+        let mut sequences = vec![];
+        for task in tasks {
+            sequences.push(task.await.unwrap());
+        }
+        sequences.sort_unstable();
+        assert_eq!(sequences, vec![25, 26]);
+        // queried again once none is in flight
+        assert_eq!(manager.current(address).await.unwrap().sequence, 30);
+        assert!(requests.recv().await.unwrap().starts_with(&account_query));
+    }
+
+    #[tokio::test]
+    async fn test_resync_on_sequence_mismatch() {
+        let (url, mut requests) = mock_server_sequence(vec![
+            ("200 OK", account(25)),
+            ("200 OK", account(30)),
+            ("200 OK", account(31)),
+        ])
+        .await;
+        let manager = manager(url);
+        let address = "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf";
+        let account_query = format!("GET /cosmos/auth/v1beta1/accounts/{} ", address);
+        let mut signed = vec![];
+        let sent = manager
+            .send(address, |current| {
+                signed.push(current.sequence);
+                let sent = if current.sequence == 27 {
+                    result(0, "[]")
+                } else {
+                    result(
+                        32,
+                        "account sequence mismatch, expected 27, got 25: incorrect account sequence",
+                    )
+                };
+                async { sent }
+            })
+            .await
+            .unwrap();
+        assert!(sent.is_ok());
+        assert_eq!(signed, vec![25, 27]);
+        assert_eq!(manager.current(address).await.unwrap().sequence, 28);
+        assert!(requests.recv().await.unwrap().starts_with(&account_query));
+
+        // a rejected transaction, the sequence is queried again
+        let sent = manager
+            .send(address, |_| async { result(5, "insufficient funds") })
+            .await;
+        assert!(matches!(sent, Err(Error::NodeError { code: 5, .. })));
+        assert_eq!(manager.current(address).await.unwrap().sequence, 30);
+        assert!(requests.recv().await.unwrap().starts_with(&account_query));
+
+        // unknown outcome, the sequence is queried again
+        let sent = manager
+            .send(address, |current| async move {
+                assert_eq!(current.sequence, 30);
+                Err(Error::TransportError("connection reset".to_string()))
+            })
+            .await;
+        assert!(matches!(sent, Err(Error::TransportError(_))));
+        assert_eq!(manager.current(address).await.unwrap().sequence, 31);
+        assert!(requests.recv().await.unwrap().starts_with(&account_query));
+        assert!(requests.try_recv().is_err());
+    }
+}
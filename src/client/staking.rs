@@ -0,0 +1,337 @@
+use super::pagination::{PageStream, Pagination};
+use super::Client;
+use crate::error::Error;
+use crate::proto::cosmos::staking::v1beta1::query_client::QueryClient as StakingQueryClient;
+use crate::proto::cosmos::staking::v1beta1::{
+    BondStatus, DelegationResponse, Params, Pool, QueryDelegatorDelegationsRequest,
+    QueryDelegatorUnbondingDelegationsRequest, QueryParamsRequest, QueryPoolRequest,
+    QueryRedelegationsRequest, QueryValidatorRequest, QueryValidatorsRequest, RedelegationResponse,
+    UnbondingDelegation, Validator,
+};
+use crate::types::address::Address;
+
+/// name of a bond status in queries
+fn bond_status_name(status: BondStatus) -> &'static str {
+    match status {
+        BondStatus::Unspecified => "BOND_STATUS_UNSPECIFIED",
+        BondStatus::Unbonded => "BOND_STATUS_UNBONDED",
+        BondStatus::Unbonding => "BOND_STATUS_UNBONDING",
+        BondStatus::Bonded => "BOND_STATUS_BONDED",
+    }
+}
+
+impl Client {
+    /// all validators, only the ones with `status` if given
+    pub fn validators(
+        &self,
+        status: Option<BondStatus>,
+        pagination: Pagination,
+    ) -> PageStream<Validator> {
+        let status = status.map(bond_status_name).unwrap_or_default();
+        self.paginate_grpc(
+            pagination,
+            move |page| QueryValidatorsRequest {
+                status: status.to_string(),
+                pagination: Some(page),
+            },
+            |channel, request| async move { StakingQueryClient::new(channel).validators(request).await },
+            |response| (response.validators, response.pagination),
+        )
+    }
+
+    /// validator with the operator address `validator`
+    pub async fn get_validator(&self, validator: &Address) -> Result<Validator, Error> {
+        let request = QueryValidatorRequest {
+            validator_addr: validator.validator_bech32()?,
+        };
+        let response = self
+            .cosmos_grpc(request, |channel, request| async move {
+                StakingQueryClient::new(channel).validator(request).await
+            })
+            .await?;
+        response
+            .validator
+            .ok_or_else(|| Error::ClientError(format!("validator {} not in response", validator)))
+    }
+
+    /// delegations of `delegator` with their balances
+    pub fn delegations(
+        &self,
+        delegator: &Address,
+        pagination: Pagination,
+    ) -> PageStream<DelegationResponse> {
+        let delegator = match delegator.account_bech32() {
+            Ok(delegator) => delegator,
+            Err(e) => return PageStream::error(e),
+        };
+        self.paginate_grpc(
+            pagination,
+            move |page| QueryDelegatorDelegationsRequest {
+                delegator_addr: delegator.clone(),
+                pagination: Some(page),
+            },
+            |channel, request| async move {
+                StakingQueryClient::new(channel)
+                    .delegator_delegations(request)
+                    .await
+            },
+            |response| (response.delegation_responses, response.pagination),
+        )
+    }
+
+    /// unbonding delegations of `delegator`
+    pub fn unbonding_delegations(
+        &self,
+        delegator: &Address,
+        pagination: Pagination,
+    ) -> PageStream<UnbondingDelegation> {
+        let delegator = match delegator.account_bech32() {
+            Ok(delegator) => delegator,
+            Err(e) => return PageStream::error(e),
+        };
+        self.paginate_grpc(
+            pagination,
+            move |page| QueryDelegatorUnbondingDelegationsRequest {
+                delegator_addr: delegator.clone(),
+                pagination: Some(page),
+            },
+            |channel, request| async move {
+                StakingQueryClient::new(channel)
+                    .delegator_unbonding_delegations(request)
+                    .await
+            },
+            |response| (response.unbonding_responses, response.pagination),
+        )
+    }
+
+    /// redelegations of `delegator` from any to any validator
+    pub fn redelegations(
+        &self,
+        delegator: &Address,
+        pagination: Pagination,
+    ) -> PageStream<RedelegationResponse> {
+        let delegator = match delegator.account_bech32() {
+            Ok(delegator) => delegator,
+            Err(e) => return PageStream::error(e),
+        };
+        self.paginate_grpc(
+            pagination,
+            move |page| QueryRedelegationsRequest {
+                delegator_addr: delegator.clone(),
+                src_validator_addr: String::new(),
+                dst_validator_addr: String::new(),
+                pagination: Some(page),
+            },
+            |channel, request| async move {
+                StakingQueryClient::new(channel)
+                    .redelegations(request)
+                    .await
+            },
+            |response| (response.redelegation_responses, response.pagination),
+        )
+    }
+
+    /// bonded and not bonded tokens of the staking pool
+    pub async fn get_staking_pool(&self) -> Result<Pool, Error> {
+        let response = self
+            .cosmos_grpc(QueryPoolRequest {}, |channel, request| async move {
+                StakingQueryClient::new(channel).pool(request).await
+            })
+            .await?;
+        response
+            .pool
+            .ok_or_else(|| Error::ClientError("no pool in response".to_string()))
+    }
+
+    /// parameters of the staking module, e.g. the unbonding time
+    pub async fn get_staking_params(&self) -> Result<Params, Error> {
+        let response = self
+            .cosmos_grpc(QueryParamsRequest {}, |channel, request| async move {
+                StakingQueryClient::new(channel).params(request).await
+            })
+            .await?;
+        response
+            .params
+            .ok_or_else(|| Error::ClientError("no params in response".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::mock::mock_grpc_incoming;
+    use super::*;
+    use crate::proto::cosmos::base::query::v1beta1::PageResponse;
+    use crate::proto::cosmos::staking::v1beta1::query_server::{Query, QueryServer};
+    use crate::proto::cosmos::staking::v1beta1::*;
+    use futures::TryStreamExt;
+    use tokio::sync::mpsc::UnboundedSender;
+    use tonic::{Request, Response, Status};
+
+    /// two validators on two pages, and the staking pool
+    struct MockStaking {
+        page_keys: UnboundedSender<Vec<u8>>,
+    }
+
+    fn unimplemented<T>() -> Result<Response<T>, Status> {
+        Err(Status::unimplemented("not mocked"))
+    }
+
+    #[tonic::async_trait]
+    impl Query for MockStaking {
+        async fn validators(
+            &self,
+            request: Request<QueryValidatorsRequest>,
+        ) -> Result<Response<QueryValidatorsResponse>, Status> {
+            let request = request.into_inner();
+            assert_eq!(request.status, "BOND_STATUS_BONDED");
+            let page = request.pagination.unwrap_or_default();
+            assert_eq!(page.limit, 1);
+            self.page_keys.send(page.key.clone()).unwrap();
+            let (operator_address, next_key) = if page.key.is_empty() {
+                ("crocncl1first", vec![1])
+            } else {
+                ("crocncl1second", vec![])
+            };
+            Ok(Response::new(QueryValidatorsResponse {
+                validators: vec![Validator {
+                    operator_address: operator_address.to_string(),
+                    ..Default::default()
+                }],
+                pagination: Some(PageResponse {
+                    next_key,
+                    total: if page.count_total { 2 } else { 0 },
+                }),
+            }))
+        }
+
+        async fn validator(
+            &self,
+            _: Request<QueryValidatorRequest>,
+        ) -> Result<Response<QueryValidatorResponse>, Status> {
+            Err(Status::not_found("validator not found"))
+        }
+
+        async fn validator_delegations(
+            &self,
+            _: Request<QueryValidatorDelegationsRequest>,
+        ) -> Result<Response<QueryValidatorDelegationsResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn validator_unbonding_delegations(
+            &self,
+            _: Request<QueryValidatorUnbondingDelegationsRequest>,
+        ) -> Result<Response<QueryValidatorUnbondingDelegationsResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn delegation(
+            &self,
+            _: Request<QueryDelegationRequest>,
+        ) -> Result<Response<QueryDelegationResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn unbonding_delegation(
+            &self,
+            _: Request<QueryUnbondingDelegationRequest>,
+        ) -> Result<Response<QueryUnbondingDelegationResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn delegator_delegations(
+            &self,
+            _: Request<QueryDelegatorDelegationsRequest>,
+        ) -> Result<Response<QueryDelegatorDelegationsResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn delegator_unbonding_delegations(
+            &self,
+            _: Request<QueryDelegatorUnbondingDelegationsRequest>,
+        ) -> Result<Response<QueryDelegatorUnbondingDelegationsResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn redelegations(
+            &self,
+            _: Request<QueryRedelegationsRequest>,
+        ) -> Result<Response<QueryRedelegationsResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn delegator_validators(
+            &self,
+            _: Request<QueryDelegatorValidatorsRequest>,
+        ) -> Result<Response<QueryDelegatorValidatorsResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn delegator_validator(
+            &self,
+            _: Request<QueryDelegatorValidatorRequest>,
+        ) -> Result<Response<QueryDelegatorValidatorResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn historical_info(
+            &self,
+            _: Request<QueryHistoricalInfoRequest>,
+        ) -> Result<Response<QueryHistoricalInfoResponse>, Status> {
+            unimplemented()
+        }
+
+        async fn pool(
+            &self,
+            _: Request<QueryPoolRequest>,
+        ) -> Result<Response<QueryPoolResponse>, Status> {
+            Ok(Response::new(QueryPoolResponse {
+                pool: Some(Pool {
+                    not_bonded_tokens: "100".to_string(),
+                    bonded_tokens: "900".to_string(),
+                }),
+            }))
+        }
+
+        async fn params(
+            &self,
+            _: Request<QueryParamsRequest>,
+        ) -> Result<Response<QueryParamsResponse>, Status> {
+            unimplemented()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validators_pagination() {
+        let (url, incoming) = mock_grpc_incoming().await;
+        let (page_keys, mut received_keys) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(QueryServer::new(MockStaking { page_keys }))
+                .serve_with_incoming(incoming),
+        );
+        let mut client = Client::new("http://127.0.0.1:1".to_string(), url.clone());
+        client.set_cosmos_grpc_url(url);
+
+        let mut pagination = Pagination::default();
+        pagination.set_limit(1).set_count_total(true);
+        let mut validators = client.validators(Some(BondStatus::Bonded), pagination);
+        let first = validators.try_next().await.unwrap().unwrap();
+        assert_eq!(first.operator_address, "crocncl1first");
+        assert_eq!(validators.total(), Some(2));
+        let rest: Vec<Validator> = validators.try_collect().await.unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].operator_address, "crocncl1second");
+        assert_eq!(received_keys.recv().await.unwrap(), Vec::<u8>::new());
+        assert_eq!(received_keys.recv().await.unwrap(), vec![1]);
+
+        let pool = client.get_staking_pool().await.unwrap();
+        assert_eq!(pool.bonded_tokens, "900");
+        let validator = Address::from_bech32("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .unwrap()
+            .to_validator();
+        let result = client.get_validator(&validator).await;
+        assert!(matches!(result, Err(Error::NodeError { code: 5, .. })));
+        assert!(client.get_staking_params().await.is_err());
+    }
+}
@@ -0,0 +1,356 @@
+use super::rpc::rpc_error;
+use crate::error::Error;
+use crate::types::broadcast::BroadcastResult;
+use crate::types::tendermint::{TxInfo, TxResult};
+use crate::utils::codec::{serde_from_base64, serde_from_str, tx_hash};
+use futures::{SinkExt, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+/// default delay before reconnecting a dropped websocket connection
+const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// number of events buffered for a slow consumer
+const SUBSCRIPTION_BUFFER: usize = 100;
+
+/// id of the JSON-RPC subscribe request
+const SUBSCRIBE_ID: u64 = 0;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// query matching transactions with all the given event attributes,
+/// e.g. `[("transfer.recipient", "cro1...")]` gives "tm.event='Tx' AND transfer.recipient='cro1...'";
+/// the Tendermint query syntax has no escapes, so keys and values with quotes are rejected
+pub fn tx_query(attributes: &[(&str, &str)]) -> Result<String, Error> {
+    let mut query = "tm.event='Tx'".to_string();
+    for (key, value) in attributes {
+        let valid_key = !key.is_empty()
+            && !key
+                .chars()
+                .any(|c| c.is_whitespace() || "\\()\"'=<>".contains(c));
+        if !valid_key || value.contains('\'') {
+            return Err(Error::InputError(format!(
+                "invalid query attribute: {}={}",
+                key, value
+            )));
+        }
+        query.push_str(&format!(" AND {}='{}'", key, value));
+    }
+    Ok(query)
+}
+
+/// committed transaction pushed by a subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxEvent {
+    /// query of the subscription
+    pub query: String,
+    /// position of the transaction in the block
+    pub index: u32,
+    /// raw transaction bytes
+    pub tx: Vec<u8>,
+    /// execution result, `timestamp` is not set
+    pub result: BroadcastResult,
+}
+
+/// client of the Tendermint RPC websocket endpoint
+#[derive(Debug, Clone)]
+pub struct SubscriptionClient {
+    url: String,
+    reconnect_delay: Duration,
+}
+
+impl SubscriptionClient {
+    /// create a new client, `rpc_url` is the Tendermint RPC url, e.g. "http://127.0.0.1:26657"
+    pub fn new(rpc_url: &str) -> Self {
+        Self {
+            url: websocket_url(rpc_url),
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+        }
+    }
+
+    /// set the delay before reconnecting a dropped connection
+    pub fn set_reconnect_delay(&mut self, reconnect_delay: Duration) -> &mut Self {
+        self.reconnect_delay = reconnect_delay;
+        self
+    }
+
+    /// websocket url, e.g. "ws://127.0.0.1:26657/websocket"
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// subscribe to transactions matching `query`, e.g. built with `tx_query`;
+    /// fails if the first connection or subscription fails, afterwards the connection is
+    /// re-established and the query resubscribed until the subscription is dropped.
+    /// Transactions committed while disconnected are not delivered.
+    pub async fn subscribe(&self, query: impl Into<String>) -> Result<Subscription, Error> {
+        let query = query.into();
+        let socket = connect(&self.url, &query).await?;
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        tokio::spawn(run(
+            self.url.clone(),
+            query.clone(),
+            self.reconnect_delay,
+            socket,
+            sender,
+        ));
+        Ok(Subscription { query, receiver })
+    }
+}
+
+/// stream of transactions matching a query, an item is an error if the pushed event is invalid
+pub struct Subscription {
+    query: String,
+    receiver: mpsc::Receiver<Result<TxEvent, Error>>,
+}
+
+impl Subscription {
+    /// query of the subscription
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+}
+
+impl Stream for Subscription {
+    type Item = Result<TxEvent, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// "ws://host:port/websocket" of "http://host:port", "wss://" of "https://"
+fn websocket_url(rpc_url: &str) -> String {
+    let url = rpc_url.trim_end_matches('/');
+    let url = if let Some(rest) = url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        url.to_string()
+    };
+    if url.ends_with("/websocket") {
+        url
+    } else {
+        format!("{}/websocket", url)
+    }
+}
+
+fn websocket_error(e: tokio_tungstenite::tungstenite::Error) -> Error {
+    Error::TransportError(format!("websocket error: {}", e))
+}
+
+/// connect and subscribe to `query`
+async fn connect(url: &str, query: &str) -> Result<WsStream, Error> {
+    let (mut socket, _) = connect_async(url).await.map_err(websocket_error)?;
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": "subscribe",
+        "id": SUBSCRIBE_ID,
+        "params": { "query": query },
+    });
+    socket
+        .send(Message::Text(request.to_string()))
+        .await
+        .map_err(websocket_error)?;
+    while let Some(message) = socket.next().await {
+        if let Message::Text(text) = message.map_err(websocket_error)? {
+            let response = parse_message(&text)?;
+            if let Some(error) = rpc_error(&response) {
+                return Err(error);
+            }
+            // {'jsonrpc': '2.0', 'id': 0, 'result': {}}
+            if response["id"] == SUBSCRIBE_ID {
+                return Ok(socket);
+            }
+        }
+    }
+    Err(Error::TransportError(
+        "websocket closed before subscribing".to_string(),
+    ))
+}
+
+/// forward events, reconnecting and resubscribing until the subscription is dropped
+async fn run(
+    url: String,
+    query: String,
+    reconnect_delay: Duration,
+    mut socket: WsStream,
+    sender: mpsc::Sender<Result<TxEvent, Error>>,
+) {
+    loop {
+        tokio::select! {
+            result = forward(&mut socket, &query, &sender) => {
+                if let Err(e) = result {
+                    log::warn!("subscription {} dropped: {}", query, e);
+                }
+            }
+            _ = sender.closed() => {}
+        }
+        let _ = socket.close(None).await;
+        loop {
+            if sender.is_closed() {
+                return;
+            }
+            tokio::time::sleep(reconnect_delay).await;
+            match connect(&url, &query).await {
+                Ok(new_socket) => {
+                    socket = new_socket;
+                    break;
+                }
+                Err(e) => log::warn!("resubscribe {} failed: {}", query, e),
+            }
+        }
+    }
+}
+
+/// forward events until the connection is closed or fails
+async fn forward(
+    socket: &mut WsStream,
+    query: &str,
+    sender: &mpsc::Sender<Result<TxEvent, Error>>,
+) -> Result<(), Error> {
+    while let Some(message) = socket.next().await {
+        let text = match message.map_err(websocket_error)? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let message = parse_message(&text)?;
+        if let Some(error) = rpc_error(&message) {
+            return Err(error);
+        }
+        let event = match parse_tx_event(query, &message) {
+            Ok(Some(event)) => Ok(event),
+            Ok(None) => continue,
+            Err(e) => Err(e),
+        };
+        if sender.send(event).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn parse_message(text: &str) -> Result<Value, Error> {
+    serde_json::from_str(text)
+        .map_err(|e| Error::ClientError(format!("invalid websocket message {}: {}", text, e)))
+}
+
+/// transaction of a pushed event, `None` for other messages
+fn parse_tx_event(query: &str, message: &Value) -> Result<Option<TxEvent>, Error> {
+    // {'jsonrpc': '2.0', 'id': 0, 'result': {'query': "tm.event='Tx'", 'data': {'type': 'tendermint/event/Tx', 'value': {'TxResult': {'height': '1024', 'index': 0, 'tx': '...', 'result': {'log': '[...]', 'gas_wanted': '200000', 'gas_used': '62545', 'events': [...]}}}}, 'events': {...}}}
+    let data = &message["result"]["data"];
+    if data["type"] != "tendermint/event/Tx" {
+        return Ok(None);
+    }
+    let event: EventTxResult = serde_json::from_value(data["value"]["TxResult"].clone())
+        .map_err(|e| Error::ClientError(format!("invalid tx event {}: {}", data, e)))?;
+    let info = TxInfo {
+        hash: tx_hash(&event.tx),
+        height: event.height,
+        index: event.index,
+        tx_result: event.result,
+        tx: event.tx,
+    };
+    Ok(Some(TxEvent {
+        query: query.to_string(),
+        index: info.index,
+        result: info.result(),
+        tx: info.tx,
+    }))
+}
+
+/// `TxResult` of a Tendermint tx event
+#[derive(Deserialize)]
+struct EventTxResult {
+    #[serde(deserialize_with = "serde_from_str")]
+    height: u64,
+    #[serde(default)]
+    index: u32,
+    #[serde(deserialize_with = "serde_from_base64")]
+    tx: Vec<u8>,
+    result: TxResult,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    const TX_EVENT: &str = r#"{"jsonrpc":"2.0","id":0,"result":{"query":"tm.event='Tx' AND transfer.recipient='cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9'","data":{"type":"tendermint/event/Tx","value":{"TxResult":{"height":"1024","index":1,"tx":"AQID","result":{"log":"[]","gas_wanted":"200000","gas_used":"62545","events":[{"type":"transfer","attributes":[{"key":"cmVjaXBpZW50","value":"Y3JvMWZqNmpwbXV5a3ZyYTRreHJ3MGNwMjBlNHZ4NHI4ZWRhOHEzeW45","index":true},{"key":"YW1vdW50","value":"MTAwMDAwMDAwYmFzZWNybw==","index":true}]}]}}}},"events":{"tm.event":["Tx"]}}}"#;
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            websocket_url("http://127.0.0.1:26657"),
+            "ws://127.0.0.1:26657/websocket"
+        );
+        assert_eq!(
+            websocket_url("https://rpc.mainnet.crypto.org/"),
+            "wss://rpc.mainnet.crypto.org/websocket"
+        );
+        assert_eq!(
+            tx_query(&[("transfer.recipient", "cro1")]).unwrap(),
+            "tm.event='Tx' AND transfer.recipient='cro1'"
+        );
+        assert!(tx_query(&[("transfer.recipient", "cro1' OR tm.event='Tx")]).is_err());
+        assert!(tx_query(&[("transfer.recipient='cro1' OR a", "b")]).is_err());
+        assert!(tx_query(&[("", "cro1")]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_resubscribe() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (queries, mut received_queries) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            // the connection is closed after one event, the client reconnects
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut socket = accept_async(stream).await.unwrap();
+                let request = socket.next().await.unwrap().unwrap().into_text().unwrap();
+                let request: Value = serde_json::from_str(&request).unwrap();
+                queries.send(request["params"]["query"].clone()).unwrap();
+                let ack = r#"{"jsonrpc":"2.0","id":0,"result":{}}"#;
+                socket.send(Message::Text(ack.to_string())).await.unwrap();
+                socket
+                    .send(Message::Text(TX_EVENT.to_string()))
+                    .await
+                    .unwrap();
+                socket.close(None).await.unwrap();
+            }
+        });
+
+        let query = tx_query(&[(
+            "transfer.recipient",
+            "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9",
+        )])
+        .unwrap();
+        let mut client = SubscriptionClient::new(&format!("http://{}", addr));
+        client.set_reconnect_delay(Duration::from_millis(10));
+        let mut subscription = client.subscribe(query.clone()).await.unwrap();
+        for _ in 0..2 {
+            let event = subscription.next().await.unwrap().unwrap();
+            assert_eq!(event.tx, vec![1, 2, 3]);
+            assert_eq!(event.index, 1);
+            assert_eq!(event.result.height, 1024);
+            assert_eq!(event.result.hash, tx_hash(&[1, 2, 3]));
+            let transfer = event.result.events_of("transfer").next().unwrap();
+            assert_eq!(
+                transfer.attribute("recipient"),
+                Some("cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9")
+            );
+            assert_eq!(transfer.attribute("amount"), Some("100000000basecro"));
+            assert_eq!(received_queries.recv().await.unwrap(), query.as_str());
+        }
+    }
+}
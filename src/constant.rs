@@ -4,5 +4,13 @@ pub const CRO: u64 = 100_000_000;
 pub static COIN_TYPE: u32 = 394;
 /// mainnet bech32 address prefix
 pub static ACCOUNT_ADDRESS_PREFIX: &str = "cro";
+/// default multiplier applied to simulated gas
+pub static DEFAULT_GAS_ADJUSTMENT: f64 = 1.3;
 /// HD path
 pub static FUNDRAISER_PATH: &str = "m/44'/394'/0'/0/0";
+/// testnet bech32 address prefix
+pub static TESTNET_ACCOUNT_ADDRESS_PREFIX: &str = "tcro";
+/// appended to the account address prefix for validator operator addresses
+pub static VALIDATOR_ADDRESS_SUFFIX: &str = "cncl";
+/// appended to the account address prefix for validator consensus addresses
+pub static CONSENSUS_ADDRESS_SUFFIX: &str = "cnclcons";
@@ -1,4 +1,5 @@
 use crate::hd_wallet::mnemonic::MnemonicError;
+use crate::types::broadcast::SdkError;
 use hdwallet::secp256k1;
 use thiserror::Error;
 
@@ -30,11 +31,63 @@ pub enum Error {
     #[error("prost encode error")]
     ProstEncodeError(#[from] prost::EncodeError),
 
-    /// json-rpc error
+    /// protobuf decoding error
+    #[cfg(feature = "grpc")]
+    #[error("prost decode error")]
+    ProstDecodeError(#[from] prost::DecodeError),
+
+    /// json-rpc error, failed connections and timeouts are `TransportError`s
     #[error("client request error")]
-    RequestError(#[from] reqwest::Error),
+    RequestError(reqwest::Error),
+
+    /// account does not exist on chain, e.g. it was never funded
+    #[error("account not found: {0}")]
+    AccountNotFound(String),
+
+    /// request or transaction rejected by the node
+    #[error("node error, code: {code}, codespace: {codespace}, log: {raw_log}")]
+    NodeError {
+        /// ABCI or gRPC status code
+        code: u32,
+        /// module the code belongs to, e.g. "sdk"
+        codespace: String,
+        /// error log of the node
+        raw_log: String,
+    },
+
+    /// connection to the node failed
+    #[error("transport error: {0}")]
+    TransportError(String),
+
+    /// the node did not finish the operation in time, e.g. a transaction was not committed
+    #[error("timeout: {0}")]
+    Timeout(String),
 
     /// general API error
     #[error("client error: {0}")]
     ClientError(String),
 }
+
+impl Error {
+    /// mapped Cosmos SDK error of an `Error::NodeError`
+    pub fn sdk_error(&self) -> Option<SdkError> {
+        match self {
+            Error::NodeError {
+                code,
+                codespace,
+                raw_log,
+            } => SdkError::from_code(codespace, *code, raw_log),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_connect() || e.is_timeout() || e.is_request() {
+            Error::TransportError(format!("request to node failed: {}", e))
+        } else {
+            Error::RequestError(e)
+        }
+    }
+}
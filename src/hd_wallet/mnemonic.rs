@@ -1,6 +1,7 @@
 use crate::types::key::PrivateKey;
 
 use crate::constant::FUNDRAISER_PATH;
+use crate::network::Network;
 use bip39::{Language, MnemonicType, Seed};
 use hdwallet::{ChainPath, KeyChain};
 use hdwallet::{DefaultKeyChain, ExtendedPrivKey};
@@ -62,9 +63,19 @@ impl Mnemonic {
         Ok(m)
     }
 
-    /// Generates private key
+    /// Generates private key of the mainnet HD path
     pub fn private_key(&self) -> Result<PrivateKey, MnemonicError> {
-        let chain_path = ChainPath::from(FUNDRAISER_PATH);
+        self.private_key_with_path(FUNDRAISER_PATH)
+    }
+
+    /// Generates private key of the HD path of `network`
+    pub fn private_key_for_network(&self, network: &Network) -> Result<PrivateKey, MnemonicError> {
+        self.private_key_with_path(&network.hd_path())
+    }
+
+    /// Generates private key of a HD path, e.g. "m/44'/394'/0'/0/0"
+    pub fn private_key_with_path(&self, path: &str) -> Result<PrivateKey, MnemonicError> {
+        let chain_path = ChainPath::from(path);
         let password = self.password.clone().unwrap_or_default();
         let seed = Seed::new(&self.inner_mnemonic, &password)
             .as_bytes()
@@ -5,6 +5,8 @@ pub mod ledger_service;
 pub mod private_key_service;
 
 use crate::error::Error;
+use crate::network::Network;
+use crate::types::address::{Address as AccountAddress, AddressKind};
 use crate::types::key::PublicKey;
 use async_trait::async_trait;
 use stdtx::Address;
@@ -18,6 +20,11 @@ pub trait KeyService {
     /// Address returns a Bitcoin style account addresses: RIPEMD160(SHA256(pubkey))
     fn address(&self) -> Result<Address, Error>;
 
+    /// bech32 account address on `network`
+    fn account_address(&self, network: &Network) -> Result<AccountAddress, Error> {
+        Ok(network.address(AddressKind::Account, self.address()?))
+    }
+
     /// sign a message, return base64 encoded string
     async fn sign(&self, msg: &[u8]) -> Result<String, Error>;
 }
@@ -1,5 +1,6 @@
 use crate::error::Error;
 use crate::hd_wallet::mnemonic::Mnemonic;
+use crate::network::Network;
 use crate::types::key::{PrivateKey, PublicKey};
 
 use crate::key_service::KeyService;
@@ -22,6 +23,15 @@ impl PrivateKeyService {
         Ok(Self { private_key })
     }
 
+    /// create a new KeyService from Mnemonic with the HD path of `network`
+    pub fn new_from_mnemonic_for_network(
+        mnemonic: Mnemonic,
+        network: &Network,
+    ) -> Result<Self, Error> {
+        let private_key = mnemonic.private_key_for_network(network)?;
+        Ok(Self { private_key })
+    }
+
     /// create a new KeyService from a PrivateKey
     pub fn new(private_key: PrivateKey) -> Self {
         Self { private_key }
@@ -19,6 +19,11 @@ pub mod key_service;
 pub mod ledger_app;
 /// transaction message types
 pub mod message;
+/// in-process mock chain node for integration tests
+#[cfg(feature = "mock-node")]
+pub mod mock_node;
+/// network profiles (chain id, address prefixes, denominations, endpoints)
+pub mod network;
 /// protobuf types
 #[cfg(feature = "grpc")]
 pub mod proto;
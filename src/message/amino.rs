@@ -1,10 +1,11 @@
-use crate::constant::ACCOUNT_ADDRESS_PREFIX;
+use crate::error::Error;
+use crate::network::Network;
+use crate::types::address::Address;
 use crate::types::basic::Amount;
-use serde::Serialize;
-use stdtx::Address;
+use serde::{Deserialize, Serialize};
 
 /// the message in Tx
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Message<V: Serialize> {
     #[serde(rename = "type")]
     /// msg identifier
@@ -13,8 +14,11 @@ pub struct Message<V: Serialize> {
     pub value: V,
 }
 
+/// message of any type, e.g. for parsing transactions received from wallets
+pub type AnyMessage = Message<serde_json::Value>;
+
 /// the value in Transfer
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct TransferValue {
     /// sender
     pub from_address: String,
@@ -26,12 +30,16 @@ pub struct TransferValue {
 
 impl TransferValue {
     /// create a new TransferValue
-    pub fn new(from_address: Address, to_address: Address, amount: Amount) -> Self {
-        Self {
-            from_address: from_address.to_bech32(ACCOUNT_ADDRESS_PREFIX),
-            to_address: to_address.to_bech32(ACCOUNT_ADDRESS_PREFIX),
+    pub fn new(
+        from_address: &Address,
+        to_address: &Address,
+        amount: Amount,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            from_address: from_address.account_bech32()?,
+            to_address: to_address.account_bech32()?,
             amount: vec![amount],
-        }
+        })
     }
 }
 
@@ -40,11 +48,419 @@ pub type Transfer = Message<TransferValue>;
 
 impl Transfer {
     /// create a new transfer message
-    pub fn new(from_address: Address, to_address: Address, amount: Amount) -> Self {
-        let transfer_value = TransferValue::new(from_address, to_address, amount);
-        Self {
+    pub fn new(
+        from_address: &Address,
+        to_address: &Address,
+        amount: Amount,
+    ) -> Result<Self, Error> {
+        let transfer_value = TransferValue::new(from_address, to_address, amount)?;
+        Ok(Self {
             transfer_type: "cosmos-sdk/MsgSend".into(),
             value: transfer_value,
+        })
+    }
+
+    /// create a new transfer message on `network`, the amount is sent in its base denomination
+    pub fn with_network(
+        network: &Network,
+        from_address: &Address,
+        to_address: &Address,
+        amount: Amount,
+    ) -> Result<Self, Error> {
+        network.check_address(from_address)?;
+        network.check_address(to_address)?;
+        Self::new(from_address, to_address, amount.on_network(network))
+    }
+}
+
+/// input or output of a multi-send
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultiSendEntry {
+    /// sender (for inputs) or receiver (for outputs)
+    pub address: String,
+    /// coins to send or receive
+    pub coins: Vec<Amount>,
+}
+
+impl MultiSendEntry {
+    /// create a new multi-send input or output
+    pub fn new(address: &Address, coins: Vec<Amount>) -> Result<Self, Error> {
+        Ok(Self {
+            address: address.account_bech32()?,
+            coins,
+        })
+    }
+}
+
+/// the value in MultiSend
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultiSendValue {
+    /// senders
+    pub inputs: Vec<MultiSendEntry>,
+    /// receivers
+    pub outputs: Vec<MultiSendEntry>,
+}
+
+impl MultiSendValue {
+    /// create a new MultiSendValue, the sum of inputs must equal the sum of outputs
+    pub fn new(inputs: Vec<MultiSendEntry>, outputs: Vec<MultiSendEntry>) -> Result<Self, Error> {
+        if inputs.is_empty() || outputs.is_empty() {
+            return Err(Error::InputError(
+                "multi-send requires at least one input and one output".to_string(),
+            ));
+        }
+        let input_coins: Vec<Amount> = inputs.iter().flat_map(|i| i.coins.clone()).collect();
+        let output_coins: Vec<Amount> = outputs.iter().flat_map(|o| o.coins.clone()).collect();
+        if Amount::sum(&input_coins)? != Amount::sum(&output_coins)? {
+            return Err(Error::InputError(
+                "sum of multi-send inputs and outputs mismatch".to_string(),
+            ));
         }
+        Ok(Self { inputs, outputs })
+    }
+}
+
+/// the message in multi-send tx
+pub type MultiSend = Message<MultiSendValue>;
+
+impl MultiSend {
+    /// create a new multi-send message
+    pub fn new(inputs: Vec<MultiSendEntry>, outputs: Vec<MultiSendEntry>) -> Result<Self, Error> {
+        let multi_send_value = MultiSendValue::new(inputs, outputs)?;
+        Ok(Self {
+            transfer_type: "cosmos-sdk/MsgMultiSend".into(),
+            value: multi_send_value,
+        })
+    }
+
+    /// create a multi-send message paying `payouts` from a single sender
+    pub fn from_payouts(
+        from_address: &Address,
+        payouts: &[(Address, Amount)],
+    ) -> Result<Self, Error> {
+        let amounts: Vec<Amount> = payouts.iter().map(|(_, amount)| amount.clone()).collect();
+        let input = MultiSendEntry::new(from_address, Amount::sum(&amounts)?)?;
+        let outputs = payouts
+            .iter()
+            .map(|(address, amount)| MultiSendEntry::new(address, vec![amount.clone()]))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(vec![input], outputs)
+    }
+
+    /// create a multi-send message paying `payouts` on `network` in its base denomination
+    pub fn from_network_payouts(
+        network: &Network,
+        from_address: &Address,
+        payouts: &[(Address, Amount)],
+    ) -> Result<Self, Error> {
+        network.check_address(from_address)?;
+        let payouts = payouts
+            .iter()
+            .map(|(address, amount)| {
+                network.check_address(address)?;
+                Ok((address.clone(), amount.on_network(network)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Self::from_payouts(from_address, &payouts)
+    }
+}
+
+/// the value in IssueDenom
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IssueDenomValue {
+    /// denom identifier
+    pub id: String,
+    /// denom name
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// json schema of the nft data
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub schema: String,
+    /// denom creator
+    pub sender: String,
+}
+
+/// the message in nft denom issuing tx
+pub type IssueDenom = Message<IssueDenomValue>;
+
+impl IssueDenom {
+    /// create a new nft denom issuing message
+    pub fn new(id: String, name: String, schema: String, sender: &Address) -> Result<Self, Error> {
+        Ok(Self {
+            transfer_type: "chainmain/nft/MsgIssueDenom".into(),
+            value: IssueDenomValue {
+                id,
+                name,
+                schema,
+                sender: sender.account_bech32()?,
+            },
+        })
+    }
+}
+
+/// the value in MintNft
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MintNftValue {
+    /// token identifier
+    pub id: String,
+    /// denom of the token
+    pub denom_id: String,
+    /// token name
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// off-chain token metadata uri
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub uri: String,
+    /// on-chain token metadata
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub data: String,
+    /// denom creator
+    pub sender: String,
+    /// token owner
+    pub recipient: String,
+}
+
+/// the message in nft minting tx
+pub type MintNft = Message<MintNftValue>;
+
+impl MintNft {
+    /// create a new nft minting message
+    pub fn new(
+        id: String,
+        denom_id: String,
+        name: String,
+        uri: String,
+        data: String,
+        sender: &Address,
+        recipient: &Address,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            transfer_type: "chainmain/nft/MsgMintNFT".into(),
+            value: MintNftValue {
+                id,
+                denom_id,
+                name,
+                uri,
+                data,
+                sender: sender.account_bech32()?,
+                recipient: recipient.account_bech32()?,
+            },
+        })
+    }
+}
+
+/// the value in EditNft
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EditNftValue {
+    /// token identifier
+    pub id: String,
+    /// denom of the token
+    pub denom_id: String,
+    /// token name
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// off-chain token metadata uri
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub uri: String,
+    /// on-chain token metadata
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub data: String,
+    /// token owner
+    pub sender: String,
+}
+
+/// the message in nft editing tx
+pub type EditNft = Message<EditNftValue>;
+
+impl EditNft {
+    /// create a new nft editing message
+    pub fn new(
+        id: String,
+        denom_id: String,
+        name: String,
+        uri: String,
+        data: String,
+        sender: &Address,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            transfer_type: "chainmain/nft/MsgEditNFT".into(),
+            value: EditNftValue {
+                id,
+                denom_id,
+                name,
+                uri,
+                data,
+                sender: sender.account_bech32()?,
+            },
+        })
+    }
+}
+
+/// the value in TransferNft
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransferNftValue {
+    /// token identifier
+    pub id: String,
+    /// denom of the token
+    pub denom_id: String,
+    /// current token owner
+    pub sender: String,
+    /// new token owner
+    pub recipient: String,
+}
+
+/// the message in nft transfer tx
+pub type TransferNft = Message<TransferNftValue>;
+
+impl TransferNft {
+    /// create a new nft transfer message
+    pub fn new(
+        id: String,
+        denom_id: String,
+        sender: &Address,
+        recipient: &Address,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            transfer_type: "chainmain/nft/MsgTransferNFT".into(),
+            value: TransferNftValue {
+                id,
+                denom_id,
+                sender: sender.account_bech32()?,
+                recipient: recipient.account_bech32()?,
+            },
+        })
+    }
+}
+
+/// the value in BurnNft
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BurnNftValue {
+    /// token identifier
+    pub id: String,
+    /// denom of the token
+    pub denom_id: String,
+    /// token owner
+    pub sender: String,
+}
+
+/// the message in nft burning tx
+pub type BurnNft = Message<BurnNftValue>;
+
+impl BurnNft {
+    /// create a new nft burning message
+    pub fn new(id: String, denom_id: String, sender: &Address) -> Result<Self, Error> {
+        Ok(Self {
+            transfer_type: "chainmain/nft/MsgBurnNFT".into(),
+            value: BurnNftValue {
+                id,
+                denom_id,
+                sender: sender.account_bech32()?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn to_json<V: Serialize>(msg: &Message<V>) -> Value {
+        serde_json::to_value(msg).unwrap()
+    }
+
+    #[test]
+    fn test_nft_messages() {
+        let sender = Address::from_bech32("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf").unwrap();
+        let recipient = Address::from_bech32("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd").unwrap();
+        let issue =
+            IssueDenom::new("edition".into(), "Edition".into(), "".into(), &sender).unwrap();
+        assert_eq!(
+            to_json(&issue),
+            json!({
+                "type": "chainmain/nft/MsgIssueDenom",
+                "value": {
+                    "id": "edition",
+                    "name": "Edition",
+                    "sender": "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf"
+                }
+            })
+        );
+        let mint = MintNft::new(
+            "token1".into(),
+            "edition".into(),
+            "First".into(),
+            "ipfs://token1".into(),
+            "{\"edition\":1}".into(),
+            &sender,
+            &recipient,
+        )
+        .unwrap();
+        assert_eq!(
+            to_json(&mint),
+            json!({
+                "type": "chainmain/nft/MsgMintNFT",
+                "value": {
+                    "id": "token1",
+                    "denom_id": "edition",
+                    "name": "First",
+                    "uri": "ipfs://token1",
+                    "data": "{\"edition\":1}",
+                    "sender": "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf",
+                    "recipient": "cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd"
+                }
+            })
+        );
+        let edit = EditNft::new(
+            "token1".into(),
+            "edition".into(),
+            "".into(),
+            "ipfs://token1-v2".into(),
+            "".into(),
+            &sender,
+        )
+        .unwrap();
+        assert_eq!(
+            to_json(&edit),
+            json!({
+                "type": "chainmain/nft/MsgEditNFT",
+                "value": {
+                    "id": "token1",
+                    "denom_id": "edition",
+                    "uri": "ipfs://token1-v2",
+                    "sender": "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf"
+                }
+            })
+        );
+        let transfer =
+            TransferNft::new("token1".into(), "edition".into(), &sender, &recipient).unwrap();
+        assert_eq!(
+            to_json(&transfer),
+            json!({
+                "type": "chainmain/nft/MsgTransferNFT",
+                "value": {
+                    "id": "token1",
+                    "denom_id": "edition",
+                    "sender": "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf",
+                    "recipient": "cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd"
+                }
+            })
+        );
+        let burn = BurnNft::new("token1".into(), "edition".into(), &recipient).unwrap();
+        assert_eq!(
+            to_json(&burn),
+            json!({
+                "type": "chainmain/nft/MsgBurnNFT",
+                "value": {
+                    "id": "token1",
+                    "denom_id": "edition",
+                    "sender": "cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd"
+                }
+            })
+        );
+
+        // the sender must be an account address
+        let validator = sender.to_validator();
+        assert!(BurnNft::new("token1".into(), "edition".into(), &validator).is_err());
     }
 }
@@ -1,9 +1,23 @@
 //! Transaction messages
 
+use crate::error::Error;
+use crate::proto::chainmain::nft::v1::{
+    MsgBurnNft, MsgEditNft, MsgIssueDenom, MsgMintNft, MsgTransferNft,
+};
+use crate::proto::cosmos::authz::v1beta1::{
+    GenericAuthorization, Grant, MsgExec, MsgGrant, MsgRevoke,
+};
+use crate::proto::cosmos::bank::v1beta1::SendAuthorization;
+use crate::proto::cosmos::base::v1beta1::Coin;
+use crate::proto::cosmos::staking::v1beta1::{
+    stake_authorization, AuthorizationType, StakeAuthorization,
+};
+use crate::types::address::Address;
 use prost_types::Any;
+use std::time::SystemTime;
 
 /// Transaction messages
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Msg(Any);
 
 impl Msg {
@@ -14,6 +28,202 @@ impl Msg {
             value: value.into(),
         })
     }
+
+    /// Create a new message from a protobuf encodable message
+    pub fn from_proto<M: prost::Message>(
+        type_url: impl Into<String>,
+        msg: &M,
+    ) -> Result<Self, Error> {
+        let mut buf = vec![];
+        prost::Message::encode(msg, &mut buf)?;
+        Ok(Msg::new(type_url, buf))
+    }
+
+    /// Create a chain-main nft denom issuing message
+    pub fn issue_denom(
+        id: String,
+        name: String,
+        schema: String,
+        sender: &Address,
+    ) -> Result<Self, Error> {
+        let msg = MsgIssueDenom {
+            id,
+            name,
+            schema,
+            sender: sender.account_bech32()?,
+        };
+        Msg::from_proto("/chainmain.nft.v1.MsgIssueDenom", &msg)
+    }
+
+    /// Create a chain-main nft minting message
+    pub fn mint_nft(
+        id: String,
+        denom_id: String,
+        name: String,
+        uri: String,
+        data: String,
+        sender: &Address,
+        recipient: &Address,
+    ) -> Result<Self, Error> {
+        let msg = MsgMintNft {
+            id,
+            denom_id,
+            name,
+            uri,
+            data,
+            sender: sender.account_bech32()?,
+            recipient: recipient.account_bech32()?,
+        };
+        Msg::from_proto("/chainmain.nft.v1.MsgMintNFT", &msg)
+    }
+
+    /// Create a chain-main nft editing message
+    pub fn edit_nft(
+        id: String,
+        denom_id: String,
+        name: String,
+        uri: String,
+        data: String,
+        sender: &Address,
+    ) -> Result<Self, Error> {
+        let msg = MsgEditNft {
+            id,
+            denom_id,
+            name,
+            uri,
+            data,
+            sender: sender.account_bech32()?,
+        };
+        Msg::from_proto("/chainmain.nft.v1.MsgEditNFT", &msg)
+    }
+
+    /// Create a chain-main nft transfer message
+    pub fn transfer_nft(
+        id: String,
+        denom_id: String,
+        sender: &Address,
+        recipient: &Address,
+    ) -> Result<Self, Error> {
+        let msg = MsgTransferNft {
+            id,
+            denom_id,
+            sender: sender.account_bech32()?,
+            recipient: recipient.account_bech32()?,
+        };
+        Msg::from_proto("/chainmain.nft.v1.MsgTransferNFT", &msg)
+    }
+
+    /// Create a chain-main nft burning message
+    pub fn burn_nft(id: String, denom_id: String, sender: &Address) -> Result<Self, Error> {
+        let msg = MsgBurnNft {
+            id,
+            denom_id,
+            sender: sender.account_bech32()?,
+        };
+        Msg::from_proto("/chainmain.nft.v1.MsgBurnNFT", &msg)
+    }
+
+    /// Create an authz message granting `authorization` to `grantee` until `expiration`
+    pub fn grant(
+        granter: &Address,
+        grantee: &Address,
+        authorization: Authorization,
+        expiration: SystemTime,
+    ) -> Result<Self, Error> {
+        let msg = MsgGrant {
+            granter: granter.account_bech32()?,
+            grantee: grantee.account_bech32()?,
+            grant: Some(Grant {
+                authorization: Some(authorization.to_any()?),
+                expiration: Some(expiration.into()),
+            }),
+        };
+        Msg::from_proto("/cosmos.authz.v1beta1.MsgGrant", &msg)
+    }
+
+    /// Create an authz message executing `msgs` on behalf of their granters
+    pub fn exec(grantee: &Address, msgs: Vec<Msg>) -> Result<Self, Error> {
+        let msg = MsgExec {
+            grantee: grantee.account_bech32()?,
+            msgs: msgs.into_iter().map(Any::from).collect(),
+        };
+        Msg::from_proto("/cosmos.authz.v1beta1.MsgExec", &msg)
+    }
+
+    /// Create an authz message revoking the grant for messages of `msg_type_url`
+    pub fn revoke(
+        granter: &Address,
+        grantee: &Address,
+        msg_type_url: impl Into<String>,
+    ) -> Result<Self, Error> {
+        let msg = MsgRevoke {
+            granter: granter.account_bech32()?,
+            grantee: grantee.account_bech32()?,
+            msg_type_url: msg_type_url.into(),
+        };
+        Msg::from_proto("/cosmos.authz.v1beta1.MsgRevoke", &msg)
+    }
+}
+
+/// Authorization granted to a grantee with `MsgGrant`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Authorization {
+    /// permission to execute any message of a type url
+    Generic(GenericAuthorization),
+    /// permission to send coins up to a spend limit
+    Send(SendAuthorization),
+    /// permission to delegate, undelegate or redelegate
+    Stake(StakeAuthorization),
+}
+
+impl Authorization {
+    /// permission to execute any message of `msg_type_url`, e.g. "/cosmos.gov.v1beta1.MsgVote"
+    pub fn generic(msg_type_url: impl Into<String>) -> Self {
+        Authorization::Generic(GenericAuthorization {
+            msg: msg_type_url.into(),
+        })
+    }
+
+    /// permission to send coins up to `spend_limit`
+    pub fn send(spend_limit: Vec<Coin>) -> Self {
+        Authorization::Send(SendAuthorization { spend_limit })
+    }
+
+    /// permission to stake only with the validators in `allow_list` (operator addresses),
+    /// up to `max_tokens` if given
+    pub fn stake(
+        authorization_type: AuthorizationType,
+        allow_list: &[Address],
+        max_tokens: Option<Coin>,
+    ) -> Result<Self, Error> {
+        let validators = stake_authorization::Validators {
+            address: allow_list
+                .iter()
+                .map(Address::validator_bech32)
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Authorization::Stake(StakeAuthorization {
+            max_tokens,
+            authorization_type: authorization_type as i32,
+            policy: Some(stake_authorization::Policy::AllowList(validators)),
+        }))
+    }
+
+    /// authorization encoded as protobuf Any
+    pub fn to_any(&self) -> Result<Any, Error> {
+        let msg = match self {
+            Authorization::Generic(authorization) => {
+                Msg::from_proto("/cosmos.authz.v1beta1.GenericAuthorization", authorization)?
+            }
+            Authorization::Send(authorization) => {
+                Msg::from_proto("/cosmos.bank.v1beta1.SendAuthorization", authorization)?
+            }
+            Authorization::Stake(authorization) => {
+                Msg::from_proto("/cosmos.staking.v1beta1.StakeAuthorization", authorization)?
+            }
+        };
+        Ok(msg.into())
+    }
 }
 
 impl From<Any> for Msg {
@@ -33,3 +243,148 @@ impl From<&Msg> for Any {
         msg.0.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use prost::Message;
+
+    fn decode<M: Message + Default>(msg: Msg, type_url: &str) -> M {
+        let any = Any::from(msg);
+        assert_eq!(any.type_url, type_url);
+        M::decode(any.value.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_nft_messages() {
+        let sender = Address::from_bech32("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf").unwrap();
+        let recipient = Address::from_bech32("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd").unwrap();
+
+        let issue = Msg::issue_denom("edition".into(), "Edition".into(), "".into(), &sender);
+        let issue: MsgIssueDenom = decode(issue.unwrap(), "/chainmain.nft.v1.MsgIssueDenom");
+        assert_eq!(issue.id, "edition");
+        assert_eq!(issue.name, "Edition");
+        assert_eq!(issue.sender, sender.to_string());
+
+        let mint = Msg::mint_nft(
+            "token1".into(),
+            "edition".into(),
+            "First".into(),
+            "ipfs://token1".into(),
+            "{}".into(),
+            &sender,
+            &recipient,
+        );
+        let mint: MsgMintNft = decode(mint.unwrap(), "/chainmain.nft.v1.MsgMintNFT");
+        assert_eq!(mint.denom_id, "edition");
+        assert_eq!(mint.uri, "ipfs://token1");
+        assert_eq!(mint.recipient, recipient.to_string());
+
+        let edit = Msg::edit_nft(
+            "token1".into(),
+            "edition".into(),
+            "".into(),
+            "ipfs://token1-v2".into(),
+            "".into(),
+            &sender,
+        );
+        let edit: MsgEditNft = decode(edit.unwrap(), "/chainmain.nft.v1.MsgEditNFT");
+        assert_eq!(edit.uri, "ipfs://token1-v2");
+
+        let transfer = Msg::transfer_nft("token1".into(), "edition".into(), &sender, &recipient);
+        let transfer: MsgTransferNft =
+            decode(transfer.unwrap(), "/chainmain.nft.v1.MsgTransferNFT");
+        assert_eq!(transfer.sender, sender.to_string());
+        assert_eq!(transfer.recipient, recipient.to_string());
+
+        let burn = Msg::burn_nft("token1".into(), "edition".into(), &recipient);
+        let burn: MsgBurnNft = decode(burn.unwrap(), "/chainmain.nft.v1.MsgBurnNFT");
+        assert_eq!(burn.id, "token1");
+
+        assert!(Msg::burn_nft("token1".into(), "edition".into(), &sender.to_validator()).is_err());
+    }
+
+    #[test]
+    fn test_authorization() {
+        let generic = Authorization::generic("/cosmos.gov.v1beta1.MsgVote")
+            .to_any()
+            .unwrap();
+        assert_eq!(
+            generic.type_url,
+            "/cosmos.authz.v1beta1.GenericAuthorization"
+        );
+        let generic = GenericAuthorization::decode(generic.value.as_slice()).unwrap();
+        assert_eq!(generic.msg, "/cosmos.gov.v1beta1.MsgVote");
+
+        let limit = Coin {
+            denom: "basecro".to_string(),
+            amount: "100".to_string(),
+        };
+        let send = Authorization::send(vec![limit.clone()]).to_any().unwrap();
+        assert_eq!(send.type_url, "/cosmos.bank.v1beta1.SendAuthorization");
+        let send = SendAuthorization::decode(send.value.as_slice()).unwrap();
+        assert_eq!(send.spend_limit, vec![limit.clone()]);
+
+        let validator = Address::from_bech32("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .unwrap()
+            .to_validator();
+        let stake = Authorization::stake(
+            AuthorizationType::Delegate,
+            &[validator.clone()],
+            Some(limit),
+        )
+        .unwrap();
+        let any = stake.to_any().unwrap();
+        assert_eq!(any.type_url, "/cosmos.staking.v1beta1.StakeAuthorization");
+        assert_eq!(
+            Authorization::Stake(StakeAuthorization::decode(any.value.as_slice()).unwrap()),
+            stake
+        );
+        match stake {
+            Authorization::Stake(StakeAuthorization {
+                authorization_type,
+                policy: Some(stake_authorization::Policy::AllowList(allow_list)),
+                ..
+            }) => {
+                assert_eq!(authorization_type, AuthorizationType::Delegate as i32);
+                assert_eq!(allow_list.address, vec![validator.to_string()]);
+            }
+            other => panic!("unexpected authorization {:?}", other),
+        }
+
+        // the allow list only takes validator operator addresses
+        let account = validator.to_account();
+        assert!(Authorization::stake(AuthorizationType::Delegate, &[account], None).is_err());
+    }
+
+    #[test]
+    fn test_authz_messages() {
+        let granter = Address::from_bech32("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf").unwrap();
+        let grantee = Address::from_bech32("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd").unwrap();
+        let expiration = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+
+        let authorization = Authorization::generic("/cosmos.bank.v1beta1.MsgSend");
+        let grant = Msg::grant(&granter, &grantee, authorization.clone(), expiration);
+        let grant: MsgGrant = decode(grant.unwrap(), "/cosmos.authz.v1beta1.MsgGrant");
+        assert_eq!(grant.granter, granter.to_string());
+        assert_eq!(grant.grantee, grantee.to_string());
+        let grant = grant.grant.unwrap();
+        assert_eq!(grant.authorization, Some(authorization.to_any().unwrap()));
+        assert_eq!(grant.expiration.unwrap().seconds, 1_700_000_000);
+
+        let burn = Msg::burn_nft("token1".into(), "edition".into(), &granter).unwrap();
+        let exec = Msg::exec(&grantee, vec![burn.clone()]);
+        let exec: MsgExec = decode(exec.unwrap(), "/cosmos.authz.v1beta1.MsgExec");
+        assert_eq!(exec.grantee, grantee.to_string());
+        assert_eq!(exec.msgs, vec![Any::from(burn)]);
+
+        let revoke = Msg::revoke(&granter, &grantee, "/cosmos.bank.v1beta1.MsgSend");
+        let revoke: MsgRevoke = decode(revoke.unwrap(), "/cosmos.authz.v1beta1.MsgRevoke");
+        assert_eq!(revoke.granter, granter.to_string());
+        assert_eq!(revoke.msg_type_url, "/cosmos.bank.v1beta1.MsgSend");
+
+        // granter and grantee must be account addresses
+        let validator = granter.to_validator();
+        assert!(Msg::revoke(&validator, &grantee, "/cosmos.bank.v1beta1.MsgSend").is_err());
+    }
+}
@@ -7,3 +7,7 @@ pub use amino::*;
 mod grpc;
 #[cfg(feature = "grpc")]
 pub use grpc::*;
+
+/// decoding protobuf messages into typed messages
+#[cfg(feature = "grpc")]
+pub mod registry;
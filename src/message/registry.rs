@@ -0,0 +1,251 @@
+//! Decoding protobuf `Any` messages back into typed messages
+
+use crate::error::Error;
+use crate::proto::chainmain::nft::v1::{
+    MsgBurnNft, MsgEditNft, MsgIssueDenom, MsgMintNft, MsgTransferNft,
+};
+use crate::proto::cosmos::authz::v1beta1::{MsgExec, MsgGrant, MsgRevoke};
+use crate::proto::cosmos::bank::v1beta1::{MsgMultiSend, MsgSend};
+use crate::proto::cosmos::distribution::v1beta1::{
+    MsgFundCommunityPool, MsgSetWithdrawAddress, MsgWithdrawDelegatorReward,
+    MsgWithdrawValidatorCommission,
+};
+use crate::proto::cosmos::gov::v1beta1::{MsgDeposit, MsgSubmitProposal, MsgVote, MsgVoteWeighted};
+use crate::proto::cosmos::staking::v1beta1::{
+    MsgBeginRedelegate, MsgCreateValidator, MsgDelegate, MsgEditValidator, MsgUndelegate,
+};
+use crate::proto::cosmos::tx::v1beta1::{TxBody, TxRaw};
+use crate::proto::ibc::applications::transfer::v1::MsgTransfer;
+use crate::proto::ibc::core::channel::v1::{MsgAcknowledgement, MsgRecvPacket, MsgTimeout};
+use crate::proto::ibc::core::client::v1::MsgUpdateClient;
+use prost_types::Any;
+use std::collections::HashMap;
+
+/// Known transaction messages
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedMsg {
+    /// bank send
+    Send(MsgSend),
+    /// bank multi-send
+    MultiSend(MsgMultiSend),
+    /// staking delegation
+    Delegate(MsgDelegate),
+    /// staking undelegation
+    Undelegate(MsgUndelegate),
+    /// staking redelegation
+    BeginRedelegate(MsgBeginRedelegate),
+    /// validator creation
+    CreateValidator(MsgCreateValidator),
+    /// validator description or commission update
+    EditValidator(MsgEditValidator),
+    /// delegation rewards withdrawal
+    WithdrawDelegatorReward(MsgWithdrawDelegatorReward),
+    /// validator commission withdrawal
+    WithdrawValidatorCommission(MsgWithdrawValidatorCommission),
+    /// rewards withdrawal address update
+    SetWithdrawAddress(MsgSetWithdrawAddress),
+    /// community pool funding
+    FundCommunityPool(MsgFundCommunityPool),
+    /// governance proposal submission
+    SubmitProposal(MsgSubmitProposal),
+    /// governance vote
+    Vote(MsgVote),
+    /// weighted governance vote
+    VoteWeighted(MsgVoteWeighted),
+    /// governance proposal deposit
+    Deposit(MsgDeposit),
+    /// ibc fungible token transfer
+    IbcTransfer(MsgTransfer),
+    /// ibc light client update
+    IbcUpdateClient(MsgUpdateClient),
+    /// ibc packet receipt
+    IbcRecvPacket(MsgRecvPacket),
+    /// ibc packet acknowledgement
+    IbcAcknowledgement(MsgAcknowledgement),
+    /// ibc packet timeout
+    IbcTimeout(MsgTimeout),
+    /// nft denom issuing
+    IssueDenom(MsgIssueDenom),
+    /// nft minting
+    MintNft(MsgMintNft),
+    /// nft editing
+    EditNft(MsgEditNft),
+    /// nft transfer
+    TransferNft(MsgTransferNft),
+    /// nft burning
+    BurnNft(MsgBurnNft),
+    /// authz grant
+    Grant(MsgGrant),
+    /// authz execution
+    Exec(MsgExec),
+    /// authz revocation
+    Revoke(MsgRevoke),
+    /// message without a registered decoder
+    Unknown(Any),
+}
+
+type Decoder = Box<dyn Fn(&[u8]) -> Result<DecodedMsg, prost::DecodeError> + Send + Sync>;
+
+/// Maps message type urls to decoders
+pub struct MsgRegistry {
+    decoders: HashMap<String, Decoder>,
+}
+
+impl MsgRegistry {
+    /// create an empty registry, every message is decoded as `DecodedMsg::Unknown`
+    pub fn empty() -> Self {
+        Self {
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// register a decoder for messages of `type_url`, replacing any previous one
+    pub fn register<M, F>(&mut self, type_url: impl Into<String>, wrap: F) -> &mut Self
+    where
+        M: prost::Message + Default + 'static,
+        F: Fn(M) -> DecodedMsg + Send + Sync + 'static,
+    {
+        let decoder = move |bytes: &[u8]| M::decode(bytes).map(&wrap);
+        self.decoders.insert(type_url.into(), Box::new(decoder));
+        self
+    }
+
+    /// whether messages of `type_url` can be decoded
+    pub fn contains(&self, type_url: &str) -> bool {
+        self.decoders.contains_key(type_url)
+    }
+
+    /// decode a message, unregistered type urls yield `DecodedMsg::Unknown`
+    pub fn decode(&self, any: &Any) -> Result<DecodedMsg, Error> {
+        match self.decoders.get(&any.type_url) {
+            Some(decoder) => Ok(decoder(&any.value)?),
+            None => Ok(DecodedMsg::Unknown(any.clone())),
+        }
+    }
+
+    /// decode all messages of a transaction body
+    pub fn decode_tx_body(&self, body: &TxBody) -> Result<Vec<DecodedMsg>, Error> {
+        body.messages.iter().map(|any| self.decode(any)).collect()
+    }
+
+    /// decode all messages of a raw transaction
+    pub fn decode_tx_raw(&self, tx_raw: &TxRaw) -> Result<Vec<DecodedMsg>, Error> {
+        let body: TxBody = prost::Message::decode(&*tx_raw.body_bytes)?;
+        self.decode_tx_body(&body)
+    }
+}
+
+impl Default for MsgRegistry {
+    /// registry of all messages known to this crate
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register("/cosmos.bank.v1beta1.MsgSend", DecodedMsg::Send)
+            .register("/cosmos.bank.v1beta1.MsgMultiSend", DecodedMsg::MultiSend)
+            .register("/cosmos.staking.v1beta1.MsgDelegate", DecodedMsg::Delegate)
+            .register(
+                "/cosmos.staking.v1beta1.MsgUndelegate",
+                DecodedMsg::Undelegate,
+            )
+            .register(
+                "/cosmos.staking.v1beta1.MsgBeginRedelegate",
+                DecodedMsg::BeginRedelegate,
+            )
+            .register(
+                "/cosmos.staking.v1beta1.MsgCreateValidator",
+                DecodedMsg::CreateValidator,
+            )
+            .register(
+                "/cosmos.staking.v1beta1.MsgEditValidator",
+                DecodedMsg::EditValidator,
+            )
+            .register(
+                "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+                DecodedMsg::WithdrawDelegatorReward,
+            )
+            .register(
+                "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission",
+                DecodedMsg::WithdrawValidatorCommission,
+            )
+            .register(
+                "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress",
+                DecodedMsg::SetWithdrawAddress,
+            )
+            .register(
+                "/cosmos.distribution.v1beta1.MsgFundCommunityPool",
+                DecodedMsg::FundCommunityPool,
+            )
+            .register(
+                "/cosmos.gov.v1beta1.MsgSubmitProposal",
+                DecodedMsg::SubmitProposal,
+            )
+            .register("/cosmos.gov.v1beta1.MsgVote", DecodedMsg::Vote)
+            .register(
+                "/cosmos.gov.v1beta1.MsgVoteWeighted",
+                DecodedMsg::VoteWeighted,
+            )
+            .register("/cosmos.gov.v1beta1.MsgDeposit", DecodedMsg::Deposit)
+            .register(
+                "/ibc.applications.transfer.v1.MsgTransfer",
+                DecodedMsg::IbcTransfer,
+            )
+            .register(
+                "/ibc.core.client.v1.MsgUpdateClient",
+                DecodedMsg::IbcUpdateClient,
+            )
+            .register(
+                "/ibc.core.channel.v1.MsgRecvPacket",
+                DecodedMsg::IbcRecvPacket,
+            )
+            .register(
+                "/ibc.core.channel.v1.MsgAcknowledgement",
+                DecodedMsg::IbcAcknowledgement,
+            )
+            .register("/ibc.core.channel.v1.MsgTimeout", DecodedMsg::IbcTimeout)
+            .register("/chainmain.nft.v1.MsgIssueDenom", DecodedMsg::IssueDenom)
+            .register("/chainmain.nft.v1.MsgMintNFT", DecodedMsg::MintNft)
+            .register("/chainmain.nft.v1.MsgEditNFT", DecodedMsg::EditNft)
+            .register("/chainmain.nft.v1.MsgTransferNFT", DecodedMsg::TransferNft)
+            .register("/chainmain.nft.v1.MsgBurnNFT", DecodedMsg::BurnNft)
+            .register("/cosmos.authz.v1beta1.MsgGrant", DecodedMsg::Grant)
+            .register("/cosmos.authz.v1beta1.MsgExec", DecodedMsg::Exec)
+            .register("/cosmos.authz.v1beta1.MsgRevoke", DecodedMsg::Revoke);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::message::Msg;
+    use crate::proto::cosmos::base::v1beta1::Coin;
+
+    #[test]
+    fn test_decode_msg() {
+        let registry = MsgRegistry::default();
+        let send = MsgSend {
+            from_address: "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf".to_string(),
+            to_address: "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9".to_string(),
+            amount: vec![Coin {
+                denom: "basecro".to_string(),
+                amount: "100000000".to_string(),
+            }],
+        };
+        let msg = Msg::from_proto("/cosmos.bank.v1beta1.MsgSend", &send).unwrap();
+        let decoded = registry.decode(&msg.into()).unwrap();
+        assert_eq!(decoded, DecodedMsg::Send(send));
+
+        let unknown = Any {
+            type_url: "/cosmos.feegrant.v1beta1.MsgGrantAllowance".to_string(),
+            value: vec![1, 2, 3],
+        };
+        let decoded = registry.decode(&unknown).unwrap();
+        assert_eq!(decoded, DecodedMsg::Unknown(unknown));
+
+        let invalid = Any {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value: vec![0xff],
+        };
+        assert!(registry.decode(&invalid).is_err());
+    }
+}
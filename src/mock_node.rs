@@ -0,0 +1,984 @@
+use crate::client::Client;
+use crate::error::Error;
+#[cfg(not(feature = "grpc"))]
+use crate::message::AnyMessage;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::bank::v1beta1::MsgSend;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::tx::v1beta1::{AuthInfo, SignDoc, TxBody, TxRaw};
+use crate::types::account::BASE_ACCOUNT_TYPE_URL;
+use crate::types::address::Address;
+use crate::types::basic::SyncMode;
+use crate::types::broadcast::BroadcastResult;
+use crate::types::coin::{Coin, Coins};
+use crate::types::key::PublicKey;
+#[cfg(feature = "grpc")]
+use crate::types::key::PublicKeyWrap;
+#[cfg(feature = "grpc")]
+use crate::types::signature::Signature;
+#[cfg(not(feature = "grpc"))]
+use crate::types::transaction::Transaction;
+use crate::utils::codec::tx_hash;
+use bitcoin_hashes::{ripemd160, sha256, Hash};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+#[cfg(feature = "grpc")]
+use prost::Message;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+#[cfg(feature = "grpc")]
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+#[cfg(not(feature = "grpc"))]
+mod amino;
+#[cfg(feature = "grpc")]
+mod grpc;
+
+// gRPC status code of the REST errors
+const GRPC_NOT_FOUND: u64 = 5;
+const GRPC_UNIMPLEMENTED: u64 = 12;
+
+/// account state of the mock node
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockAccount {
+    /// account number
+    pub account_number: u64,
+    /// sequence of the next transaction
+    pub sequence: u64,
+    /// spendable coins
+    pub balances: Coins,
+    /// public key, known once the account sent a transaction
+    pub pub_key: Option<PublicKey>,
+}
+
+/// transaction broadcasted to the mock node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedTx {
+    /// signer address, empty if the transaction could not be decoded
+    pub signer: String,
+    /// result returned to the client
+    pub result: BroadcastResult,
+}
+
+/// verify the signatures with the chain id, account number and sequence
+type VerifyFn = Box<dyn FnOnce(&str, u64, u64) -> Result<(), Error> + Send>;
+
+/// transaction decoded from a broadcast request
+struct SubmittedTx {
+    hash: String,
+    mode: SyncMode,
+    pub_key: PublicKey,
+    /// sequence the signer claims
+    sequence: u64,
+    /// (from, to, amount) of the bank sends
+    sends: Vec<(String, String, Coins)>,
+    verify: VerifyFn,
+}
+
+#[derive(Default)]
+struct State {
+    chain_id: String,
+    height: u64,
+    verify_signatures: bool,
+    accounts: HashMap<String, MockAccount>,
+    responses: VecDeque<BroadcastResult>,
+    txs: HashMap<String, BroadcastResult>,
+    received: Vec<ReceivedTx>,
+}
+
+/// in-process chain node serving the REST endpoints `Client` uses
+/// (accounts, balances, supply, latest block, transaction broadcast and query),
+/// with the `grpc` feature also the auth, bank and tx services and the Tendermint broadcast API;
+/// broadcasted transactions are checked and committed in a new block right away,
+/// bank sends move the balances, fees are not deducted
+pub struct MockNode {
+    url: String,
+    #[cfg(feature = "grpc")]
+    grpc_url: String,
+    state: Arc<Mutex<State>>,
+    shutdown: watch::Sender<()>,
+}
+
+impl MockNode {
+    /// start a node of the chain `chain_id` on a random local port
+    pub async fn start(chain_id: &str) -> Result<Self, Error> {
+        let mock_error =
+            |e: &dyn std::fmt::Display| Error::ClientError(format!("mock node: {}", e));
+        let state = Arc::new(Mutex::new(State {
+            chain_id: chain_id.to_string(),
+            height: 1,
+            verify_signatures: true,
+            ..Default::default()
+        }));
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").map_err(|e| mock_error(&e))?;
+        listener.set_nonblocking(true).map_err(|e| mock_error(&e))?;
+        let addr = listener.local_addr().map_err(|e| mock_error(&e))?;
+        let service_state = state.clone();
+        let server = Server::from_tcp(listener)
+            .map_err(|e| mock_error(&e))?
+            .serve(make_service_fn(move |_| {
+                let state = service_state.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request)))
+                }
+            }));
+        let (shutdown, mut stopped) = watch::channel(());
+        #[cfg(feature = "grpc")]
+        let grpc_url = grpc::serve(state.clone(), stopped.clone()).await?;
+        tokio::spawn(server.with_graceful_shutdown(async move {
+            stopped.changed().await.ok();
+        }));
+        Ok(Self {
+            url: format!("http://{}", addr),
+            #[cfg(feature = "grpc")]
+            grpc_url,
+            state,
+            shutdown,
+        })
+    }
+
+    /// base url of the REST API
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// url of the gRPC server, both the cosmos gRPC and the Tendermint gRPC url
+    #[cfg(feature = "grpc")]
+    pub fn grpc_url(&self) -> &str {
+        &self.grpc_url
+    }
+
+    /// client of the node
+    #[cfg(not(feature = "grpc"))]
+    pub fn client(&self) -> Client {
+        Client::new(self.url.clone())
+    }
+
+    /// client of the node, queries go through the cosmos gRPC services
+    #[cfg(feature = "grpc")]
+    pub fn client(&self) -> Client {
+        let mut client = Client::new(self.url.clone(), self.grpc_url.clone());
+        client.set_cosmos_grpc_url(self.grpc_url.clone());
+        client
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("lock mock node state")
+    }
+
+    /// create or update the account number and sequence of `address`, balances are kept
+    pub fn set_account(&mut self, address: &str, account_number: u64, sequence: u64) -> &mut Self {
+        let mut state = self.state();
+        let account = state.account_mut(address);
+        account.account_number = account_number;
+        account.sequence = sequence;
+        drop(state);
+        self
+    }
+
+    /// set the balances of `address`, the account is created if unknown
+    pub fn set_balances(&mut self, address: &str, balances: Coins) -> &mut Self {
+        self.state().account_mut(address).balances = balances;
+        self
+    }
+
+    /// set the height of the latest block
+    pub fn set_height(&mut self, height: u64) -> &mut Self {
+        self.state().height = height;
+        self
+    }
+
+    /// check the signatures of broadcasted transactions, enabled by default
+    pub fn set_verify_signatures(&mut self, verify_signatures: bool) -> &mut Self {
+        self.state().verify_signatures = verify_signatures;
+        self
+    }
+
+    /// return `result` for the next broadcast instead of executing the transaction,
+    /// the hash is filled in if empty; results are used in the pushed order
+    pub fn push_broadcast_response(&mut self, result: BroadcastResult) -> &mut Self {
+        self.state().responses.push_back(result);
+        self
+    }
+
+    /// state of the account `address`
+    pub fn account(&self, address: &str) -> Option<MockAccount> {
+        self.state().accounts.get(address).cloned()
+    }
+
+    /// height of the latest block
+    pub fn height(&self) -> u64 {
+        self.state().height
+    }
+
+    /// broadcasted transactions in the received order
+    pub fn received_txs(&self) -> Vec<ReceivedTx> {
+        self.state().received.clone()
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        let _ = self.shutdown.send(());
+    }
+}
+
+impl State {
+    fn account_mut(&mut self, address: &str) -> &mut MockAccount {
+        let next_number = self.accounts.len() as u64;
+        self.accounts
+            .entry(address.to_string())
+            .or_insert_with(|| MockAccount {
+                account_number: next_number,
+                ..Default::default()
+            })
+    }
+
+    fn signer(&self, pub_key: &PublicKey) -> Option<String> {
+        let raw = raw_address(pub_key);
+        self.accounts
+            .keys()
+            .find(|address| matches!(Address::from_bech32(address), Ok(address) if address.raw() == raw))
+            .cloned()
+    }
+
+    /// check and commit a transaction, returns the broadcast result of its mode
+    fn broadcast(&mut self, tx: Result<SubmittedTx, Error>) -> BroadcastResult {
+        let tx = match tx {
+            Ok(tx) => tx,
+            Err(e) => {
+                let result = sdk_result(String::new(), 2, format!("{}: tx parse error", e));
+                self.received.push(ReceivedTx {
+                    signer: String::new(),
+                    result: result.clone(),
+                });
+                return result;
+            }
+        };
+        let signer = self.signer(&tx.pub_key).unwrap_or_default();
+        let result = if let Some(mut result) = self.responses.pop_front() {
+            if result.hash.is_empty() {
+                result.hash = tx.hash.clone();
+            }
+            result
+        } else if self.txs.contains_key(&tx.hash) {
+            sdk_result(tx.hash.clone(), 19, "tx already in mempool".to_string())
+        } else {
+            self.execute(&signer, tx)
+        };
+        self.received.push(ReceivedTx {
+            signer,
+            result: result.clone(),
+        });
+        result
+    }
+
+    fn execute(&mut self, signer: &str, tx: SubmittedTx) -> BroadcastResult {
+        let account = match self.accounts.get(signer) {
+            Some(account) => account.clone(),
+            None => {
+                let log = format!(
+                    "account {} not found: unknown address",
+                    hex_address(&tx.pub_key)
+                );
+                return sdk_result(tx.hash, 9, log);
+            }
+        };
+        if let Some((from, _, _)) = tx.sends.iter().find(|(from, _, _)| from != signer) {
+            let log = format!(
+                "pubKey does not match signer address {} with signer index: 0: unauthorized",
+                from
+            );
+            return sdk_result(tx.hash, 4, log);
+        }
+        if tx.sequence != account.sequence {
+            let log = format!(
+                "account sequence mismatch, expected {}, got {}: incorrect account sequence",
+                account.sequence, tx.sequence
+            );
+            return sdk_result(tx.hash, 32, log);
+        }
+        if self.verify_signatures
+            && (tx.verify)(&self.chain_id, account.account_number, account.sequence).is_err()
+        {
+            let log = format!(
+                "signature verification failed; please verify account number ({}), sequence ({}) and chain-id ({}): unauthorized",
+                account.account_number, account.sequence, self.chain_id
+            );
+            return sdk_result(tx.hash, 4, log);
+        }
+        let account = self.account_mut(signer);
+        account.sequence += 1;
+        account.pub_key = Some(tx.pub_key.clone());
+
+        // committed in the next block, messages are only executed in DeliverTx
+        self.height += 1;
+        let mut committed = BroadcastResult {
+            hash: tx.hash.clone(),
+            height: self.height,
+            raw_log: "[]".to_string(),
+            ..Default::default()
+        };
+        if let Err(log) = self.apply_sends(&tx.sends) {
+            committed = BroadcastResult {
+                height: self.height,
+                ..sdk_result(tx.hash.clone(), 5, log)
+            };
+        }
+        self.txs.insert(tx.hash.clone(), committed.clone());
+        match tx.mode {
+            SyncMode::Block => committed,
+            SyncMode::Sync | SyncMode::Async => BroadcastResult {
+                hash: tx.hash,
+                raw_log: "[]".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// apply all sends or none of them
+    fn apply_sends(&mut self, sends: &[(String, String, Coins)]) -> Result<(), String> {
+        let mut accounts = self.accounts.clone();
+        for (from, to, amount) in sends {
+            let balances = accounts
+                .get(from)
+                .map(|account| account.balances.clone())
+                .unwrap_or_default();
+            let remaining = balances.checked_sub(amount).ok_or_else(|| {
+                format!(
+                    "{} is smaller than {}: insufficient funds",
+                    balances, amount
+                )
+            })?;
+            accounts
+                .get_mut(from)
+                .expect("sender with balance")
+                .balances = remaining;
+            let next_number = accounts.len() as u64;
+            let recipient = accounts.entry(to.clone()).or_insert_with(|| MockAccount {
+                account_number: next_number,
+                ..Default::default()
+            });
+            recipient.balances = recipient
+                .balances
+                .checked_add(amount)
+                .ok_or_else(|| "balance overflow".to_string())?;
+        }
+        self.accounts = accounts;
+        Ok(())
+    }
+
+    fn account_json(&self, address: &str) -> (StatusCode, Value) {
+        let account = match self.accounts.get(address) {
+            Some(account) => account,
+            None => {
+                return not_found(format!(
+                    "rpc error: code = NotFound desc = account {} not found: key not found",
+                    address
+                ))
+            }
+        };
+        let pub_key = account.pub_key.as_ref().map(|pub_key| {
+            json!({
+                "@type": "/cosmos.crypto.secp256k1.PubKey",
+                "key": pub_key.to_string(),
+            })
+        });
+        ok(json!({
+            "account": {
+                "@type": BASE_ACCOUNT_TYPE_URL,
+                "address": address,
+                "pub_key": pub_key,
+                "account_number": account.account_number.to_string(),
+                "sequence": account.sequence.to_string(),
+            }
+        }))
+    }
+
+    fn balances(&self, address: &str) -> Coins {
+        self.accounts
+            .get(address)
+            .map(|account| account.balances.clone())
+            .unwrap_or_default()
+    }
+
+    fn total_supply(&self) -> Coins {
+        self.accounts
+            .values()
+            .try_fold(Coins::default(), |total, account| {
+                total.checked_add(&account.balances)
+            })
+            .unwrap_or_default()
+    }
+
+    fn route(
+        &mut self,
+        method: &Method,
+        path: &str,
+        query: &str,
+        body: &[u8],
+    ) -> (StatusCode, Value) {
+        // routed on the decoded path like grpc-gateway, an escaped slash separates segments
+        let decoded = percent_decode(path);
+        let segments: Vec<&str> = decoded.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            (&Method::GET, ["cosmos", "auth", "v1beta1", "accounts", address]) => {
+                self.account_json(address)
+            }
+            (&Method::GET, ["cosmos", "bank", "v1beta1", "balances", address]) => {
+                // all balances in one page
+                let balances: Vec<Coin> = self.balances(address).into();
+                ok(json!({
+                    "balances": balances,
+                    "pagination": {"next_key": null, "total": balances.len().to_string()},
+                }))
+            }
+            (&Method::GET, ["cosmos", "bank", "v1beta1", "balances", address, "by_denom"]) => {
+                let denom = query
+                    .split('&')
+                    .find_map(|param| param.strip_prefix("denom="))
+                    .map(percent_decode)
+                    .unwrap_or_default();
+                let amount = self.balances(address).amount_of(&denom);
+                ok(json!({"balance": {"denom": denom, "amount": amount.to_string()}}))
+            }
+            (&Method::GET, ["cosmos", "bank", "v1beta1", "supply"]) => {
+                let supply: Vec<Coin> = self.total_supply().into();
+                ok(json!({
+                    "supply": supply,
+                    "pagination": {"next_key": null, "total": supply.len().to_string()},
+                }))
+            }
+            (&Method::GET, ["cosmos", "bank", "v1beta1", "supply", denom]) => {
+                let amount = self.total_supply().amount_of(denom);
+                ok(json!({"amount": {"denom": denom, "amount": amount.to_string()}}))
+            }
+            (&Method::GET, ["cosmos", "base", "tendermint", "v1beta1", "blocks", "latest"]) => {
+                ok(json!({
+                    "block": {
+                        "header": {"chain_id": self.chain_id, "height": self.height.to_string()}
+                    }
+                }))
+            }
+            (&Method::GET, ["cosmos", "base", "tendermint", "v1beta1", "syncing"]) => {
+                ok(json!({"syncing": false}))
+            }
+            (&Method::GET, ["cosmos", "tx", "v1beta1", "txs", hash]) => match self.txs.get(*hash) {
+                Some(result) => ok(json!({"tx": null, "tx_response": result_json(result)})),
+                None => not_found(format!(
+                    "rpc error: code = NotFound desc = tx not found: {}",
+                    hash
+                )),
+            },
+            (&Method::POST, ["cosmos", "tx", "v1beta1", "txs"]) => {
+                let result = self.broadcast(decode_proto_tx(body));
+                ok(json!({"tx_response": result_json(&result)}))
+            }
+            #[cfg(not(feature = "grpc"))]
+            (&Method::POST, ["txs"]) => {
+                let result = self.broadcast(decode_amino_tx(body));
+                ok(result_json(&result))
+            }
+            _ => (
+                StatusCode::NOT_IMPLEMENTED,
+                json!({
+                    "code": GRPC_UNIMPLEMENTED,
+                    "message": format!("{} {} is not served by the mock node", method, path),
+                    "details": [],
+                }),
+            ),
+        }
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().unwrap_or_default().to_string();
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .unwrap_or_default();
+    let (status, response) = state
+        .lock()
+        .expect("lock mock node state")
+        .route(&method, &path, &query, &body);
+    let response = Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(response.to_string()))
+        .expect("valid response");
+    Ok(response)
+}
+
+/// decode `%XX` escapes of a path segment or query value
+fn percent_decode(value: &str) -> String {
+    let mut bytes = vec![];
+    let mut rest = value.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let escaped = std::str::from_utf8(tail.get(..2).unwrap_or_default())
+            .ok()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(escaped) if b == b'%' => {
+                bytes.push(escaped);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn ok(response: Value) -> (StatusCode, Value) {
+    (StatusCode::OK, response)
+}
+
+fn not_found(message: String) -> (StatusCode, Value) {
+    (
+        StatusCode::NOT_FOUND,
+        json!({"code": GRPC_NOT_FOUND, "message": message, "details": []}),
+    )
+}
+
+fn sdk_result(hash: String, code: u32, raw_log: String) -> BroadcastResult {
+    BroadcastResult {
+        hash,
+        code,
+        codespace: "sdk".to_string(),
+        raw_log,
+        ..Default::default()
+    }
+}
+
+/// RIPEMD160(SHA256(compressed public key))
+fn raw_address(pub_key: &PublicKey) -> stdtx::Address {
+    let sha = sha256::Hash::hash(&pub_key.as_ref().serialize());
+    ripemd160::Hash::hash(sha.as_inner()).into_inner().into()
+}
+
+fn hex_address(pub_key: &PublicKey) -> String {
+    raw_address(pub_key)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect()
+}
+
+/// `TxResponse` JSON of the REST API
+fn result_json(result: &BroadcastResult) -> Value {
+    json!({
+        "height": result.height.to_string(),
+        "txhash": result.hash,
+        "codespace": result.codespace,
+        "code": result.code,
+        "raw_log": result.raw_log,
+        "logs": [{"msg_index": 0, "log": "", "events": result.events}],
+        "gas_wanted": result.gas_wanted.to_string(),
+        "gas_used": result.gas_used.to_string(),
+        "timestamp": result.timestamp,
+    })
+}
+
+fn invalid_tx(e: impl std::fmt::Display) -> Error {
+    Error::InputError(format!("invalid transaction: {}", e))
+}
+
+/// `{"tx": StdTx, "mode": "sync"}` of the legacy amino endpoint
+#[cfg(not(feature = "grpc"))]
+fn decode_amino_tx(body: &[u8]) -> Result<SubmittedTx, Error> {
+    let transaction: Transaction<AnyMessage> = serde_json::from_slice(body).map_err(invalid_tx)?;
+    let signature = transaction
+        .tx
+        .signatures
+        .first()
+        .ok_or_else(|| invalid_tx("no signatures"))?;
+    let (pub_key, sequence) = (signature.pub_key.public_key()?, signature.sequence);
+    let mut sends = vec![];
+    for msg in &transaction.tx.messages {
+        if msg.transfer_type == "cosmos-sdk/MsgSend" {
+            let text = |key: &str| msg.value[key].as_str().unwrap_or_default().to_string();
+            let amount: Coins =
+                serde_json::from_value(msg.value["amount"].clone()).map_err(invalid_tx)?;
+            sends.push((text("from_address"), text("to_address"), amount));
+        }
+    }
+    let tx = transaction.tx;
+    Ok(SubmittedTx {
+        hash: tx_hash(&amino::tx_raw(&tx)?),
+        mode: transaction.mode,
+        pub_key,
+        sequence,
+        sends,
+        verify: Box::new(move |chain_id, account_number, sequence| {
+            tx.verify(chain_id, account_number, sequence)
+        }),
+    })
+}
+
+/// `{"tx_bytes": "<base64 TxRaw>", "mode": "BROADCAST_MODE_SYNC"}` of the tx service
+#[cfg(feature = "grpc")]
+fn decode_proto_tx(body: &[u8]) -> Result<SubmittedTx, Error> {
+    let request: Value = serde_json::from_slice(body).map_err(invalid_tx)?;
+    let tx_bytes =
+        base64::decode(request["tx_bytes"].as_str().unwrap_or_default()).map_err(invalid_tx)?;
+    let mode = match request["mode"].as_str() {
+        Some("BROADCAST_MODE_BLOCK") => SyncMode::Block,
+        Some("BROADCAST_MODE_ASYNC") => SyncMode::Async,
+        _ => SyncMode::Sync,
+    };
+    decode_tx_raw(&tx_bytes, mode)
+}
+
+/// encoded `TxRaw` broadcasted with `mode`
+#[cfg(feature = "grpc")]
+fn decode_tx_raw(tx_bytes: &[u8], mode: SyncMode) -> Result<SubmittedTx, Error> {
+    let raw = TxRaw::decode(tx_bytes)?;
+    let auth_info = AuthInfo::decode(raw.auth_info_bytes.as_slice())?;
+    let body = TxBody::decode(raw.body_bytes.as_slice())?;
+    let signer_info = auth_info
+        .signer_infos
+        .first()
+        .ok_or_else(|| invalid_tx("no signer infos"))?;
+    let key_any = signer_info
+        .public_key
+        .as_ref()
+        .ok_or_else(|| invalid_tx("no public key"))?;
+    let key = Vec::<u8>::decode(key_any.value.as_slice())?;
+    let pub_key = PublicKey::from_base64_str(&base64::encode(key))?;
+    let mut sends = vec![];
+    for msg in &body.messages {
+        if msg.type_url == "/cosmos.bank.v1beta1.MsgSend" {
+            let send = MsgSend::decode(msg.value.as_slice())?;
+            sends.push((
+                send.from_address,
+                send.to_address,
+                Coins::try_from(send.amount)?,
+            ));
+        }
+    }
+    let signature = Signature {
+        signature: base64::encode(raw.signatures.first().cloned().unwrap_or_default()),
+        pub_key: PublicKeyWrap::from(pub_key.clone()),
+        account_number: 0,
+        sequence: signer_info.sequence,
+    };
+    Ok(SubmittedTx {
+        hash: tx_hash(tx_bytes),
+        mode,
+        pub_key,
+        sequence: signer_info.sequence,
+        sends,
+        verify: Box::new(move |chain_id, account_number, _| {
+            let sign_doc = SignDoc {
+                body_bytes: raw.body_bytes,
+                auth_info_bytes: raw.auth_info_bytes,
+                chain_id: chain_id.to_string(),
+                account_number,
+            };
+            signature.verify(&sign_doc.encode_to_vec())
+        }),
+    })
+}
+
+#[cfg(not(feature = "grpc"))]
+fn decode_proto_tx(_body: &[u8]) -> Result<SubmittedTx, Error> {
+    Err(invalid_tx(
+        "protobuf transactions are only decoded with the grpc feature",
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::broadcast::SdkError;
+
+    const ADDRESS: &str = "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf";
+
+    #[tokio::test]
+    async fn test_queries() {
+        let mut node = MockNode::start("test").await.unwrap();
+        node.set_balances(ADDRESS, "1000basecro,5ibc/ABC".parse().unwrap())
+            .set_account(ADDRESS, 9, 25)
+            .set_height(100);
+        let client = node.client();
+        assert_eq!(client.get_account_info(ADDRESS).await.unwrap(), (9, 25));
+        assert!(matches!(
+            client
+                .get_account_info("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd")
+                .await,
+            Err(Error::AccountNotFound(_))
+        ));
+        let balances = client.get_balances(ADDRESS).await.unwrap();
+        assert_eq!(balances.amount_of("basecro"), 1000);
+        assert_eq!(
+            client.get_balance(ADDRESS, "ibc/ABC").await.unwrap(),
+            Coin::new(5, "ibc/ABC").unwrap()
+        );
+        assert_eq!(client.get_total_supply().await.unwrap(), balances);
+        assert_eq!(client.get_supply_of("ibc/ABC").await.unwrap().amount(), 5);
+        assert_eq!(client.latest_height().await.unwrap(), 100);
+        assert_eq!(client.get_tx("00").await.unwrap(), None);
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    #[tokio::test]
+    async fn test_broadcast() {
+        use crate::hd_wallet::mnemonic::Mnemonic;
+        use crate::key_service::private_key_service::PrivateKeyService;
+        use crate::message::Transfer;
+        use crate::tx_builder::TxBuilder;
+        use crate::types::basic::{Amount, Denom};
+
+        let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
+        let mnemonic = Mnemonic::from_str(words, None).unwrap();
+        let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
+        let fee = Some(Amount::try_new(1000, Denom::Basecro).unwrap());
+        let mut builder = TxBuilder::new(key_service, "test".to_string(), None, fee, None);
+        let sender = builder.sender_address().unwrap();
+        let recipient = builder
+            .network
+            .parse_address("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd")
+            .unwrap();
+        let transfer = |amount| {
+            Transfer::new(
+                &sender,
+                &recipient,
+                Amount::try_new(amount, Denom::Basecro).unwrap(),
+            )
+            .unwrap()
+        };
+
+        let mut node = MockNode::start("test").await.unwrap();
+        node.set_balances(&sender.to_string(), "1000basecro".parse().unwrap())
+            .set_account(&sender.to_string(), 9, 25);
+        let client = node.client();
+
+        builder
+            .add_message(transfer(600))
+            .set_account_number(9)
+            .set_sequence(25);
+        let tx = builder.build(SyncMode::Block).await.unwrap();
+        let result = client.broadcast_tx(tx.clone()).await.unwrap();
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(result.height, 2);
+        // the hash of the protobuf transaction the amino one is converted to
+        let decoded: Transaction<AnyMessage> =
+            serde_json::from_value(serde_json::to_value(&tx).unwrap()).unwrap();
+        assert_eq!(result.hash, tx_hash(&amino::tx_raw(&decoded.tx).unwrap()));
+        assert_eq!(client.get_tx(&result.hash).await.unwrap(), Some(result));
+        assert_eq!(
+            client.get_account_info(&sender.to_string()).await.unwrap(),
+            (9, 26)
+        );
+        let received = client
+            .get_balance(&recipient.to_string(), "basecro")
+            .await
+            .unwrap();
+        assert_eq!(received.amount(), 600);
+
+        // the same transaction again
+        let duplicate = client.broadcast_tx(tx).await;
+        assert!(matches!(duplicate, Err(Error::NodeError { code: 19, .. })));
+
+        // another transaction signed with the old sequence
+        builder.messages = vec![transfer(100)];
+        let tx = builder.build(SyncMode::Sync).await.unwrap();
+        let rejected = client.broadcast_tx(tx).await.unwrap_err();
+        assert!(matches!(
+            rejected.sdk_error(),
+            Some(SdkError::WrongSequence {
+                expected: Some(26),
+                ..
+            })
+        ));
+
+        // committed, but the transfer fails
+        builder.messages = vec![transfer(600)];
+        builder.set_sequence(26);
+        let tx = builder.build(SyncMode::Sync).await.unwrap();
+        let accepted = client.broadcast_tx(tx).await.unwrap();
+        assert_eq!((accepted.code, accepted.height), (0, 0));
+        let committed = client.get_tx(&accepted.hash).await.unwrap().unwrap();
+        assert_eq!((committed.code, committed.height), (5, 3));
+        assert_eq!(node.account(&sender.to_string()).unwrap().sequence, 27);
+
+        // sending the coins of another account
+        let foreign = Transfer::new(
+            &recipient,
+            &sender,
+            Amount::try_new(1, Denom::Basecro).unwrap(),
+        )
+        .unwrap();
+        builder.messages = vec![foreign];
+        builder.set_sequence(27);
+        let tx = builder.build(SyncMode::Sync).await.unwrap();
+        let unauthorized = client.broadcast_tx(tx).await.unwrap_err();
+        assert_eq!(unauthorized.sdk_error(), Some(SdkError::Unauthorized));
+        assert_eq!(node.account(&sender.to_string()).unwrap().sequence, 27);
+
+        node.push_broadcast_response(BroadcastResult {
+            code: 20,
+            codespace: "sdk".to_string(),
+            raw_log: "mempool is full".to_string(),
+            ..Default::default()
+        });
+        builder.set_sequence(27);
+        let tx = builder.build(SyncMode::Sync).await.unwrap();
+        let full = client.broadcast_tx(tx).await.unwrap_err();
+        assert_eq!(full.sdk_error(), Some(SdkError::MempoolIsFull));
+        let received = node.received_txs();
+        assert_eq!(received.len(), 6);
+        assert!(received.iter().all(|tx| tx.signer == sender.to_string()));
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    #[tokio::test]
+    async fn test_sequence_resync() {
+        use crate::client::sequence::SequenceManager;
+        use crate::hd_wallet::mnemonic::Mnemonic;
+        use crate::key_service::private_key_service::PrivateKeyService;
+        use crate::message::Transfer;
+        use crate::tx_builder::TxBuilder;
+        use crate::types::basic::{Amount, Denom};
+
+        let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
+        let mnemonic = Mnemonic::from_str(words, None).unwrap();
+        let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
+        let sender = TxBuilder::<_, Transfer>::new(
+            key_service.clone(),
+            "test".to_string(),
+            None,
+            None,
+            None,
+        )
+        .sender_address()
+        .unwrap();
+        let recipient: Address = "cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd"
+            .parse()
+            .unwrap();
+
+        let mut node = MockNode::start("test").await.unwrap();
+        node.set_balances(&sender.to_string(), "1000basecro".parse().unwrap())
+            .set_account(&sender.to_string(), 9, 25);
+        let client = node.client();
+        let manager = SequenceManager::new(client.clone());
+        assert_eq!(
+            manager.current(&sender.to_string()).await.unwrap().sequence,
+            25
+        );
+        // transactions sent by another wallet of the account
+        node.set_account(&sender.to_string(), 9, 27);
+
+        let result = manager
+            .send(&sender.to_string(), |current| {
+                let (client, key_service) = (client.clone(), key_service.clone());
+                let amount = Amount::try_new(100, Denom::Basecro).unwrap();
+                let transfer = Transfer::new(&sender, &recipient, amount).unwrap();
+                async move {
+                    let mut builder =
+                        TxBuilder::new(key_service, "test".to_string(), None, None, None);
+                    builder
+                        .add_message(transfer)
+                        .set_account_number(current.account_number)
+                        .set_sequence(current.sequence);
+                    client
+                        .broadcast_tx(builder.build(SyncMode::Sync).await?)
+                        .await
+                }
+            })
+            .await
+            .unwrap();
+        assert!(result.is_ok());
+        let codes: Vec<u32> = node
+            .received_txs()
+            .iter()
+            .map(|tx| tx.result.code)
+            .collect();
+        assert_eq!(codes, vec![32, 0]);
+        assert_eq!(node.account(&sender.to_string()).unwrap().sequence, 28);
+        assert_eq!(
+            manager.current(&sender.to_string()).await.unwrap().sequence,
+            28
+        );
+    }
+
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn test_broadcast_proto() {
+        use crate::hd_wallet::mnemonic::Mnemonic;
+        use crate::key_service::private_key_service::PrivateKeyService;
+        use crate::proto::cosmos::tx::v1beta1::Fee;
+        use crate::tx_builder::TxBuilder;
+
+        let recipient: Address = "cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd"
+            .parse()
+            .unwrap();
+        let builder = || {
+            let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
+            let mnemonic = Mnemonic::from_str(words, None).unwrap();
+            let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
+            let fee = Fee {
+                amount: vec![],
+                gas_limit: 200000,
+                payer: "".to_string(),
+                granter: "".to_string(),
+            };
+            TxBuilder::new(key_service, "test".to_string(), None, 0, Some(fee))
+        };
+        let transfer = |amount: u128, sequence: u64| {
+            let mut builder = builder();
+            let msg = builder
+                .create_msg(&recipient, builder.base_coin(amount))
+                .unwrap();
+            builder
+                .add_message(msg)
+                .set_account_number(9)
+                .set_sequence(sequence);
+            async move { builder.build_raw().await.unwrap() }
+        };
+        let sender = builder().sender_address().unwrap().to_string();
+
+        let mut node = MockNode::start("test").await.unwrap();
+        node.set_balances(&sender, "1000basecro".parse().unwrap())
+            .set_account(&sender, 9, 25);
+        let client = node.client();
+
+        // REST endpoint of the tx service
+        let result = client
+            .broadcast_tx_rest(&transfer(600, 25).await, SyncMode::Block)
+            .await
+            .unwrap();
+        assert_eq!(result.height, 2);
+        let committed = client.get_tx(&result.hash).await.unwrap().unwrap();
+        assert_eq!((committed.hash, committed.height), (result.hash, 2));
+
+        // cosmos gRPC tx service
+        let rejected = client
+            .broadcast_tx_with_mode(transfer(300, 25).await, SyncMode::Sync)
+            .await;
+        assert!(matches!(rejected, Err(Error::NodeError { code: 32, .. })));
+
+        // Tendermint gRPC broadcast API, waiting for the block
+        let result = client.broadcast_tx(transfer(300, 26).await).await.unwrap();
+        assert_eq!(result.height, 3);
+        let failed = client.broadcast_tx(transfer(300, 27).await).await;
+        assert!(matches!(failed, Err(Error::NodeError { code: 5, .. })));
+
+        assert_eq!(client.get_account_info(&sender).await.unwrap(), (9, 28));
+        let received = client
+            .get_balance(&recipient.to_string(), "basecro")
+            .await
+            .unwrap();
+        assert_eq!(received.amount(), 900);
+        assert_eq!(node.received_txs().len(), 4);
+    }
+}
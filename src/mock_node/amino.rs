@@ -0,0 +1,201 @@
+use super::invalid_tx;
+use crate::error::Error;
+use crate::message::AnyMessage;
+use crate::types::transaction::Tx;
+use serde_json::Value;
+
+/// `SIGN_MODE_LEGACY_AMINO_JSON` of the signer infos of converted amino transactions
+const SIGN_MODE_LEGACY_AMINO_JSON: u64 = 127;
+
+/// protobuf `TxRaw` a node converts the amino `tx` to before broadcasting it,
+/// its hash is the transaction hash; only bank messages are supported
+pub(super) fn tx_raw(tx: &Tx<AnyMessage>) -> Result<Vec<u8>, Error> {
+    let signature = tx
+        .signatures
+        .first()
+        .ok_or_else(|| invalid_tx("no signatures"))?;
+    let messages = tx
+        .messages
+        .iter()
+        .map(message_any)
+        .collect::<Result<Vec<_>, Error>>()?;
+    let fee = serde_json::to_value(&tx.fee).map_err(invalid_tx)?;
+    let pub_key = signature.pub_key.public_key()?;
+    let auth_info = auth_info(
+        &pub_key.as_ref().serialize(),
+        SIGN_MODE_LEGACY_AMINO_JSON,
+        signature.sequence,
+        &fee["amount"],
+        tx.fee.gas,
+    );
+    let raw_signature = base64::decode(&signature.signature).map_err(invalid_tx)?;
+    Ok(encode_tx_raw(
+        &tx_body(&messages, &tx.memo, 0),
+        &auth_info,
+        &raw_signature,
+    ))
+}
+
+/// minimal protobuf writer, zero scalars and empty strings are omitted like in proto3
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn uint64(&mut self, field: u64, value: u64) -> &mut Self {
+        if value != 0 {
+            self.varint(field << 3);
+            self.varint(value);
+        }
+        self
+    }
+
+    /// length-delimited field, always written as it is also used for repeated fields and messages
+    fn bytes(&mut self, field: u64, value: &[u8]) -> &mut Self {
+        self.varint(field << 3 | 2);
+        self.varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    fn string(&mut self, field: u64, value: &str) -> &mut Self {
+        if !value.is_empty() {
+            self.bytes(field, value.as_bytes());
+        }
+        self
+    }
+
+    /// repeated `cosmos.base.v1beta1.Coin` of a JSON coin list
+    fn coins(&mut self, field: u64, coins: &Value) -> &mut Self {
+        for coin in coins.as_array().into_iter().flatten() {
+            let coin = Writer::default()
+                .string(1, &text(&coin["denom"]))
+                .string(2, &text(&coin["amount"]))
+                .finish();
+            self.bytes(field, &coin);
+        }
+        self
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// JSON string, or a number written as text
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn any(type_url: &str, value: &[u8]) -> Vec<u8> {
+    Writer::default()
+        .string(1, type_url)
+        .bytes(2, value)
+        .finish()
+}
+
+/// `Any` of an amino message
+fn message_any(msg: &AnyMessage) -> Result<Vec<u8>, Error> {
+    let value = &msg.value;
+    match msg.transfer_type.as_str() {
+        "cosmos-sdk/MsgSend" => {
+            let send = Writer::default()
+                .string(1, &text(&value["from_address"]))
+                .string(2, &text(&value["to_address"]))
+                .coins(3, &value["amount"])
+                .finish();
+            Ok(any("/cosmos.bank.v1beta1.MsgSend", &send))
+        }
+        "cosmos-sdk/MsgMultiSend" => {
+            let mut multi_send = Writer::default();
+            for (field, entries) in [(1, &value["inputs"]), (2, &value["outputs"])] {
+                for entry in entries.as_array().into_iter().flatten() {
+                    let entry = Writer::default()
+                        .string(1, &text(&entry["address"]))
+                        .coins(2, &entry["coins"])
+                        .finish();
+                    multi_send.bytes(field, &entry);
+                }
+            }
+            Ok(any(
+                "/cosmos.bank.v1beta1.MsgMultiSend",
+                &multi_send.finish(),
+            ))
+        }
+        other => Err(invalid_tx(format!("unsupported message type {}", other))),
+    }
+}
+
+fn tx_body(messages: &[Vec<u8>], memo: &str, timeout_height: u64) -> Vec<u8> {
+    let mut body = Writer::default();
+    for message in messages {
+        body.bytes(1, message);
+    }
+    body.string(2, memo).uint64(3, timeout_height).finish()
+}
+
+fn auth_info(pub_key: &[u8], mode: u64, sequence: u64, fee: &Value, gas: u64) -> Vec<u8> {
+    let key = Writer::default().bytes(1, pub_key).finish();
+    let single = Writer::default().uint64(1, mode).finish();
+    let mode_info = Writer::default().bytes(1, &single).finish();
+    let signer_info = Writer::default()
+        .bytes(1, &any("/cosmos.crypto.secp256k1.PubKey", &key))
+        .bytes(2, &mode_info)
+        .uint64(3, sequence)
+        .finish();
+    let fee = Writer::default().coins(1, fee).uint64(2, gas).finish();
+    Writer::default()
+        .bytes(1, &signer_info)
+        .bytes(2, &fee)
+        .finish()
+}
+
+fn encode_tx_raw(body: &[u8], auth_info: &[u8], signature: &[u8]) -> Vec<u8> {
+    Writer::default()
+        .bytes(1, body)
+        .bytes(2, auth_info)
+        .bytes(3, signature)
+        .finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_encode_tx_raw() {
+        // the direct mode transaction of the protobuf builder test
+        let expected = "CpMBCo4BChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEm4KKmNybzF1OXE4bWZwemh5djJzNDNqczdsNXFzZWFweDVrdDNnMnJmN3BwZhIqY3JvMWZqNmpwbXV5a3ZyYTRreHJ3MGNwMjBlNHZ4NHI4ZWRhOHEzeW45GhQKB2Jhc2Vjcm8SCTEwMDAwMDAwMBgBEmoKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJ7S/lMTMifTWfQxi6dr+2t3ZUsYjVzGiLcjl0kcPImIhIECgIIARgEEhYKEAoHYmFzZWNybxIFMTAwMDAQ4KcSGkCOWoGjmfFFurZEhimOjj6CN68EQMAvD4iOaoQ+7iKB8iAPyXj8JHe+Z60e+GnJ5Bphn9skl96FPpn9GnGh8mfE";
+        let raw = base64::decode(expected).unwrap();
+        let signature = &raw[raw.len() - 64..];
+        let send: AnyMessage = serde_json::from_value(json!({
+            "type": "cosmos-sdk/MsgSend",
+            "value": {
+                "from_address": "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf",
+                "to_address": "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9",
+                "amount": [{"denom": "basecro", "amount": "100000000"}],
+            },
+        }))
+        .unwrap();
+        let pub_key = base64::decode("AntL+UxMyJ9NZ9DGLp2v7a3dlSxiNXMaItyOXSRw8iYi").unwrap();
+        let fee = json!([{"denom": "basecro", "amount": "10000"}]);
+        let tx = encode_tx_raw(
+            &tx_body(&[message_any(&send).unwrap()], "", 1),
+            &auth_info(&pub_key, 1, 4, &fee, 300000),
+            signature,
+        );
+        assert_eq!(base64::encode(tx), expected);
+    }
+}
@@ -0,0 +1,347 @@
+use super::{decode_tx_raw, MockAccount, State};
+use crate::error::Error;
+use crate::proto::cosmos::auth::v1beta1 as auth;
+use crate::proto::cosmos::auth::v1beta1::query_server::QueryServer as AuthQueryServer;
+use crate::proto::cosmos::bank::v1beta1 as bank;
+use crate::proto::cosmos::bank::v1beta1::query_server::QueryServer as BankQueryServer;
+use crate::proto::cosmos::base::abci::v1beta1::{
+    AbciMessageLog, Attribute, StringEvent, TxResponse,
+};
+use crate::proto::cosmos::base::query::v1beta1::PageResponse;
+use crate::proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+use crate::proto::cosmos::tx::v1beta1 as tx;
+use crate::proto::cosmos::tx::v1beta1::service_server::ServiceServer as TxServiceServer;
+use crate::proto::tendermint::abci::{
+    Event as AbciEvent, EventAttribute as AbciEventAttribute, ResponseCheckTx, ResponseDeliverTx,
+};
+use crate::proto::tendermint::rpc::grpc::broadcast_api_server::{BroadcastApi, BroadcastApiServer};
+use crate::proto::tendermint::rpc::grpc::{
+    RequestBroadcastTx, RequestPing, ResponseBroadcastTx, ResponsePing,
+};
+use crate::types::account::BASE_ACCOUNT_TYPE_URL;
+use crate::types::basic::SyncMode;
+use crate::types::broadcast::{BroadcastResult, Event};
+use prost::Message;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tonic::{Request, Response, Status};
+
+/// serve the gRPC services on a random local port until `stopped` changes, returns the url
+pub(super) async fn serve(
+    state: Arc<Mutex<State>>,
+    mut stopped: watch::Receiver<()>,
+) -> Result<String, Error> {
+    let mock_error = |e: std::io::Error| Error::ClientError(format!("mock node: {}", e));
+    let listener = TcpListener::bind("127.0.0.1:0").await.map_err(mock_error)?;
+    let addr = listener.local_addr().map_err(mock_error)?;
+    let incoming = futures::stream::unfold(listener, |listener| async move {
+        let socket = listener.accept().await.map(|(socket, _)| socket);
+        Some((socket, listener))
+    });
+    let services = Services { state };
+    let server = tonic::transport::Server::builder()
+        .add_service(AuthQueryServer::new(services.clone()))
+        .add_service(BankQueryServer::new(services.clone()))
+        .add_service(TxServiceServer::new(services.clone()))
+        .add_service(BroadcastApiServer::new(services))
+        .serve_with_incoming_shutdown(incoming, async move {
+            stopped.changed().await.ok();
+        });
+    tokio::spawn(server);
+    Ok(format!("http://{}", addr))
+}
+
+/// auth, bank and tx services and the Tendermint broadcast API on the shared node state
+#[derive(Clone)]
+struct Services {
+    state: Arc<Mutex<State>>,
+}
+
+impl Services {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("lock mock node state")
+    }
+}
+
+fn unimplemented<T>() -> Result<Response<T>, Status> {
+    Err(Status::unimplemented("not served by the mock node"))
+}
+
+fn proto_coin(denom: String, amount: u128) -> ProtoCoin {
+    ProtoCoin {
+        denom,
+        amount: amount.to_string(),
+    }
+}
+
+/// all items in one page
+fn single_page(total: usize) -> Option<PageResponse> {
+    Some(PageResponse {
+        next_key: vec![],
+        total: total as u64,
+    })
+}
+
+fn account_any(address: &str, account: &MockAccount) -> prost_types::Any {
+    // `PubKey { key: bytes = 1 }` is encoded like `BytesValue`
+    let pub_key = account.pub_key.as_ref().map(|pub_key| prost_types::Any {
+        type_url: "/cosmos.crypto.secp256k1.PubKey".to_string(),
+        value: pub_key.as_ref().serialize().to_vec().encode_to_vec(),
+    });
+    let account = auth::BaseAccount {
+        address: address.to_string(),
+        pub_key,
+        account_number: account.account_number,
+        sequence: account.sequence,
+    };
+    prost_types::Any {
+        type_url: BASE_ACCOUNT_TYPE_URL.to_string(),
+        value: account.encode_to_vec(),
+    }
+}
+
+fn string_event(event: &Event) -> StringEvent {
+    StringEvent {
+        r#type: event.kind.clone(),
+        attributes: event
+            .attributes
+            .iter()
+            .map(|attribute| Attribute {
+                key: attribute.key.clone(),
+                value: attribute.value.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn abci_event(event: &Event) -> AbciEvent {
+    AbciEvent {
+        r#type: event.kind.clone(),
+        attributes: event
+            .attributes
+            .iter()
+            .map(|attribute| AbciEventAttribute {
+                key: attribute.key.clone().into_bytes(),
+                value: attribute.value.clone().into_bytes(),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+fn tx_response(result: &BroadcastResult) -> TxResponse {
+    TxResponse {
+        height: result.height as i64,
+        txhash: result.hash.clone(),
+        codespace: result.codespace.clone(),
+        code: result.code,
+        raw_log: result.raw_log.clone(),
+        logs: vec![AbciMessageLog {
+            msg_index: 0,
+            log: String::new(),
+            events: result.events.iter().map(string_event).collect(),
+        }],
+        gas_wanted: result.gas_wanted as i64,
+        gas_used: result.gas_used as i64,
+        timestamp: result.timestamp.clone(),
+        ..Default::default()
+    }
+}
+
+#[tonic::async_trait]
+impl auth::query_server::Query for Services {
+    async fn accounts(
+        &self,
+        _: Request<auth::QueryAccountsRequest>,
+    ) -> Result<Response<auth::QueryAccountsResponse>, Status> {
+        unimplemented()
+    }
+
+    async fn account(
+        &self,
+        request: Request<auth::QueryAccountRequest>,
+    ) -> Result<Response<auth::QueryAccountResponse>, Status> {
+        let address = request.into_inner().address;
+        let state = self.state();
+        let account = state.accounts.get(&address).ok_or_else(|| {
+            Status::not_found(format!("account {} not found: key not found", address))
+        })?;
+        Ok(Response::new(auth::QueryAccountResponse {
+            account: Some(account_any(&address, account)),
+        }))
+    }
+
+    async fn params(
+        &self,
+        _: Request<auth::QueryParamsRequest>,
+    ) -> Result<Response<auth::QueryParamsResponse>, Status> {
+        unimplemented()
+    }
+}
+
+#[tonic::async_trait]
+impl bank::query_server::Query for Services {
+    async fn balance(
+        &self,
+        request: Request<bank::QueryBalanceRequest>,
+    ) -> Result<Response<bank::QueryBalanceResponse>, Status> {
+        let request = request.into_inner();
+        let amount = self
+            .state()
+            .balances(&request.address)
+            .amount_of(&request.denom);
+        Ok(Response::new(bank::QueryBalanceResponse {
+            balance: Some(proto_coin(request.denom, amount)),
+        }))
+    }
+
+    async fn all_balances(
+        &self,
+        request: Request<bank::QueryAllBalancesRequest>,
+    ) -> Result<Response<bank::QueryAllBalancesResponse>, Status> {
+        let balances: Vec<ProtoCoin> = self.state().balances(&request.into_inner().address).into();
+        Ok(Response::new(bank::QueryAllBalancesResponse {
+            pagination: single_page(balances.len()),
+            balances,
+        }))
+    }
+
+    async fn total_supply(
+        &self,
+        _: Request<bank::QueryTotalSupplyRequest>,
+    ) -> Result<Response<bank::QueryTotalSupplyResponse>, Status> {
+        let supply: Vec<ProtoCoin> = self.state().total_supply().into();
+        Ok(Response::new(bank::QueryTotalSupplyResponse {
+            pagination: single_page(supply.len()),
+            supply,
+        }))
+    }
+
+    async fn supply_of(
+        &self,
+        request: Request<bank::QuerySupplyOfRequest>,
+    ) -> Result<Response<bank::QuerySupplyOfResponse>, Status> {
+        let denom = request.into_inner().denom;
+        let amount = self.state().total_supply().amount_of(&denom);
+        Ok(Response::new(bank::QuerySupplyOfResponse {
+            amount: Some(proto_coin(denom, amount)),
+        }))
+    }
+
+    async fn params(
+        &self,
+        _: Request<bank::QueryParamsRequest>,
+    ) -> Result<Response<bank::QueryParamsResponse>, Status> {
+        unimplemented()
+    }
+
+    async fn denom_metadata(
+        &self,
+        _: Request<bank::QueryDenomMetadataRequest>,
+    ) -> Result<Response<bank::QueryDenomMetadataResponse>, Status> {
+        unimplemented()
+    }
+
+    async fn denoms_metadata(
+        &self,
+        _: Request<bank::QueryDenomsMetadataRequest>,
+    ) -> Result<Response<bank::QueryDenomsMetadataResponse>, Status> {
+        unimplemented()
+    }
+}
+
+#[tonic::async_trait]
+impl tx::service_server::Service for Services {
+    async fn simulate(
+        &self,
+        _: Request<tx::SimulateRequest>,
+    ) -> Result<Response<tx::SimulateResponse>, Status> {
+        unimplemented()
+    }
+
+    async fn get_tx(
+        &self,
+        request: Request<tx::GetTxRequest>,
+    ) -> Result<Response<tx::GetTxResponse>, Status> {
+        let hash = request.into_inner().hash;
+        let state = self.state();
+        let result = state
+            .txs
+            .get(&hash)
+            .ok_or_else(|| Status::not_found(format!("tx not found: {}", hash)))?;
+        Ok(Response::new(tx::GetTxResponse {
+            tx: None,
+            tx_response: Some(tx_response(result)),
+        }))
+    }
+
+    async fn broadcast_tx(
+        &self,
+        request: Request<tx::BroadcastTxRequest>,
+    ) -> Result<Response<tx::BroadcastTxResponse>, Status> {
+        let request = request.into_inner();
+        let mode = match tx::BroadcastMode::from_i32(request.mode) {
+            Some(tx::BroadcastMode::Block) => SyncMode::Block,
+            Some(tx::BroadcastMode::Async) => SyncMode::Async,
+            _ => SyncMode::Sync,
+        };
+        let result = self
+            .state()
+            .broadcast(decode_tx_raw(&request.tx_bytes, mode));
+        Ok(Response::new(tx::BroadcastTxResponse {
+            tx_response: Some(tx_response(&result)),
+        }))
+    }
+
+    async fn get_txs_event(
+        &self,
+        _: Request<tx::GetTxsEventRequest>,
+    ) -> Result<Response<tx::GetTxsEventResponse>, Status> {
+        unimplemented()
+    }
+}
+
+#[tonic::async_trait]
+impl BroadcastApi for Services {
+    async fn ping(&self, _: Request<RequestPing>) -> Result<Response<ResponsePing>, Status> {
+        Ok(Response::new(ResponsePing {}))
+    }
+
+    /// waits for the block like a node, a transaction rejected before it is committed
+    /// only has a check tx result
+    async fn broadcast_tx(
+        &self,
+        request: Request<RequestBroadcastTx>,
+    ) -> Result<Response<ResponseBroadcastTx>, Status> {
+        let tx = request.into_inner().tx;
+        let result = self.state().broadcast(decode_tx_raw(&tx, SyncMode::Block));
+        let events = result.events.iter().map(abci_event).collect();
+        let response = if result.height == 0 {
+            ResponseBroadcastTx {
+                check_tx: Some(ResponseCheckTx {
+                    code: result.code,
+                    codespace: result.codespace,
+                    log: result.raw_log,
+                    events,
+                    ..Default::default()
+                }),
+                deliver_tx: None,
+            }
+        } else {
+            ResponseBroadcastTx {
+                check_tx: Some(ResponseCheckTx::default()),
+                deliver_tx: Some(ResponseDeliverTx {
+                    code: result.code,
+                    codespace: result.codespace,
+                    log: result.raw_log,
+                    gas_wanted: result.gas_wanted as i64,
+                    gas_used: result.gas_used as i64,
+                    events,
+                    ..Default::default()
+                }),
+            }
+        };
+        Ok(Response::new(response))
+    }
+}
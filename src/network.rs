@@ -0,0 +1,186 @@
+use crate::constant::{
+    ACCOUNT_ADDRESS_PREFIX, COIN_TYPE, CONSENSUS_ADDRESS_SUFFIX, TESTNET_ACCOUNT_ADDRESS_PREFIX,
+    VALIDATOR_ADDRESS_SUFFIX,
+};
+use crate::error::Error;
+use crate::types::address::{Address, AddressKind};
+
+/// registered coin type of testnets in HD path
+pub const TESTNET_COIN_TYPE: u32 = 1;
+
+/// chain id, address prefixes, denominations and endpoints of a network
+#[derive(Debug, Clone, PartialEq)]
+pub struct Network {
+    /// network identifier signed into transactions
+    pub chain_id: String,
+    /// bech32 account address prefix, e.g. "cro"
+    pub account_address_prefix: String,
+    /// registered coin type in HD path
+    pub coin_type: u32,
+    /// base denomination fees and amounts are paid in, e.g. "basecro"
+    pub base_denom: String,
+    /// human denomination, e.g. "cro"
+    pub display_denom: String,
+    /// default gas price in base denomination per gas unit
+    pub gas_price: f64,
+    /// Cosmos SDK REST API url, `api.address` in app.toml
+    pub api_url: String,
+    /// Tendermint RPC url, `rpc.laddr` in config.toml
+    pub rpc_url: String,
+    /// Cosmos SDK gRPC url, `grpc.address` in app.toml
+    pub grpc_url: String,
+}
+
+impl Network {
+    /// Crypto.org Chain mainnet
+    pub fn mainnet() -> Self {
+        Self {
+            chain_id: "crypto-org-chain-mainnet-1".to_string(),
+            account_address_prefix: ACCOUNT_ADDRESS_PREFIX.to_string(),
+            coin_type: COIN_TYPE,
+            base_denom: "basecro".to_string(),
+            display_denom: "cro".to_string(),
+            gas_price: 0.025,
+            api_url: "https://rest.mainnet.crypto.org".to_string(),
+            rpc_url: "https://rpc.mainnet.crypto.org".to_string(),
+            grpc_url: "https://grpc.mainnet.crypto.org:443".to_string(),
+        }
+    }
+
+    /// Crypto.org Chain public testnet (Croeseid)
+    pub fn testnet() -> Self {
+        Self {
+            chain_id: "testnet-croeseid-4".to_string(),
+            account_address_prefix: TESTNET_ACCOUNT_ADDRESS_PREFIX.to_string(),
+            coin_type: TESTNET_COIN_TYPE,
+            base_denom: "basetcro".to_string(),
+            display_denom: "tcro".to_string(),
+            gas_price: 0.025,
+            api_url: "https://testnet-croeseid-4.crypto.org:1317".to_string(),
+            rpc_url: "https://testnet-croeseid-4.crypto.org:26657".to_string(),
+            grpc_url: "https://testnet-croeseid-4.crypto.org:9090".to_string(),
+        }
+    }
+
+    /// local development network started with the default chain-maind ports
+    pub fn devnet() -> Self {
+        Self {
+            chain_id: "chainmain-1".to_string(),
+            api_url: "http://127.0.0.1:1317".to_string(),
+            rpc_url: "http://127.0.0.1:26657".to_string(),
+            grpc_url: "http://127.0.0.1:9090".to_string(),
+            ..Self::mainnet()
+        }
+    }
+
+    /// set the chain id, e.g. for a custom devnet
+    pub fn set_chain_id(&mut self, chain_id: impl Into<String>) -> &mut Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+
+    /// set the default gas price in base denomination per gas unit
+    pub fn set_gas_price(&mut self, gas_price: f64) -> &mut Self {
+        self.gas_price = gas_price;
+        self
+    }
+
+    /// set the REST, Tendermint RPC and gRPC urls
+    pub fn set_endpoints(
+        &mut self,
+        api_url: impl Into<String>,
+        rpc_url: impl Into<String>,
+        grpc_url: impl Into<String>,
+    ) -> &mut Self {
+        self.api_url = api_url.into();
+        self.rpc_url = rpc_url.into();
+        self.grpc_url = grpc_url.into();
+        self
+    }
+
+    /// HD path of the first account, e.g. "m/44'/394'/0'/0/0"
+    pub fn hd_path(&self) -> String {
+        format!("m/44'/{}'/0'/0/0", self.coin_type)
+    }
+
+    /// bech32 validator operator address prefix, e.g. "crocncl"
+    pub fn validator_address_prefix(&self) -> String {
+        format!(
+            "{}{}",
+            self.account_address_prefix, VALIDATOR_ADDRESS_SUFFIX
+        )
+    }
+
+    /// bech32 validator consensus address prefix, e.g. "crocnclcons"
+    pub fn consensus_address_prefix(&self) -> String {
+        format!(
+            "{}{}",
+            self.account_address_prefix, CONSENSUS_ADDRESS_SUFFIX
+        )
+    }
+
+    /// parse a bech32 account, validator or consensus address of this network
+    pub fn parse_address(&self, address: &str) -> Result<Address, Error> {
+        Address::from_bech32_with_prefix(address, &self.account_address_prefix)
+    }
+
+    /// address of this network from raw bytes
+    pub fn address(&self, kind: AddressKind, raw: stdtx::Address) -> Address {
+        Address::new(self.account_address_prefix.clone(), kind, raw)
+    }
+
+    /// fail unless the address belongs to this network
+    pub fn check_address(&self, address: &Address) -> Result<(), Error> {
+        if address.prefix() != self.account_address_prefix {
+            return Err(Error::InputError(format!(
+                "address {} does not belong to network {}",
+                address, self.chain_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// fee in base denomination for `gas_limit` at the default gas price, rounded up
+    pub fn fee_amount(&self, gas_limit: u64) -> u128 {
+        (gas_limit as f64 * self.gas_price).ceil() as u128
+    }
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_network() {
+        let mainnet = Network::mainnet();
+        assert_eq!(mainnet.hd_path(), "m/44'/394'/0'/0/0");
+        assert_eq!(mainnet.validator_address_prefix(), "crocncl");
+        assert_eq!(mainnet.fee_amount(200_000), 5000);
+
+        let testnet = Network::testnet();
+        assert_eq!(testnet.hd_path(), "m/44'/1'/0'/0/0");
+        assert_eq!(testnet.consensus_address_prefix(), "tcrocnclcons");
+
+        let address = mainnet
+            .parse_address("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .unwrap();
+        assert!(mainnet.check_address(&address).is_ok());
+        assert!(testnet.check_address(&address).is_err());
+        assert!(testnet
+            .parse_address("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf")
+            .is_err());
+        let testnet_address = testnet.address(AddressKind::Account, address.raw());
+        assert!(testnet_address.to_string().starts_with("tcro1"));
+
+        let mut devnet = Network::devnet();
+        devnet.set_chain_id("test");
+        assert_eq!(devnet.chain_id, "test");
+        assert_eq!(devnet.account_address_prefix, "cro");
+    }
+}
@@ -1,6 +1,20 @@
 /// The version (commit hash) of the Cosmos SDK used when generating this library.
 pub const COSMOS_SDK_VERSION: &str = include_str!("proto/COSMOS_SDK_COMMIT");
 pub const TENDERMINT_VERSION: &str = include_str!("proto/TENDERMINT_COMMIT");
+/// The version (tag) of ibc-go used when generating the IBC types.
+pub const IBC_GO_VERSION: &str = include_str!("proto/IBC_GO_COMMIT");
+/// The version (tag) of chain-main used when generating its custom module types.
+pub const CHAIN_MAIN_VERSION: &str = include_str!("proto/CHAIN_MAIN_COMMIT");
+
+/// Crypto.org Chain custom module protobuf definitions.
+pub mod chainmain {
+    /// Non-fungible tokens.
+    pub mod nft {
+        pub mod v1 {
+            include!("proto/chainmain.nft.v1.rs");
+        }
+    }
+}
 
 /// Cosmos protobuf definitions.
 pub mod cosmos {
@@ -11,12 +25,33 @@ pub mod cosmos {
         }
     }
 
+    /// Authorization for accounts to perform actions on behalf of other accounts.
+    pub mod authz {
+        pub mod v1beta1 {
+            include!("proto/cosmos.authz.v1beta1.rs");
+        }
+    }
+
     pub mod bank {
         pub mod v1beta1 {
             include!("proto/cosmos.bank.v1beta1.rs");
         }
     }
 
+    /// Fee and reward distribution.
+    pub mod distribution {
+        pub mod v1beta1 {
+            include!("proto/cosmos.distribution.v1beta1.rs");
+        }
+    }
+
+    /// On-chain governance.
+    pub mod gov {
+        pub mod v1beta1 {
+            include!("proto/cosmos.gov.v1beta1.rs");
+        }
+    }
+
     /// Proof-of-Stake layer for public blockchains.
     pub mod staking {
         pub mod v1beta1 {
@@ -24,6 +59,13 @@ pub mod cosmos {
         }
     }
 
+    /// Vesting accounts.
+    pub mod vesting {
+        pub mod v1beta1 {
+            include!("proto/cosmos.vesting.v1beta1.rs");
+        }
+    }
+
     /// Base functionality.
     pub mod base {
         /// Application BlockChain Interface (ABCI).
@@ -1,5 +1,9 @@
 use crate::error::Error;
 use crate::key_service::KeyService;
+use crate::message::MultiSend;
+use crate::network::Network;
+use crate::tx_builder::payout::{split_payouts, PayoutBatch};
+use crate::types::address::Address;
 use crate::types::basic::{Amount, Fee, SyncMode};
 use crate::types::signature::SignDoc;
 use crate::types::signature::Signature;
@@ -11,8 +15,8 @@ use serde::Serialize;
 pub struct TxBuilder<T: KeyService + Clone, M: Serialize + Clone> {
     /// signing backend
     pub key_service: T,
-    /// network identifier
-    pub chain_id: String,
+    /// network the transactions are signed for
+    pub network: Network,
     /// messages to be executed
     pub messages: Vec<M>,
     /// extra payload
@@ -32,13 +36,26 @@ where
     T: KeyService + Clone,
     M: Serialize + Clone,
 {
-    /// instatiate new tx builder
+    /// instatiate new tx builder for a mainnet-like network with the chain id `chain_id`
     pub fn new(
         key_service: T,
         chain_id: String,
         memo: Option<String>,
         fee: Option<Amount>,
         gas: Option<u64>,
+    ) -> Self {
+        let mut network = Network::mainnet();
+        network.set_chain_id(chain_id);
+        Self::with_network(key_service, network, memo, fee, gas)
+    }
+
+    /// instatiate new tx builder for `network`
+    pub fn with_network(
+        key_service: T,
+        network: Network,
+        memo: Option<String>,
+        fee: Option<Amount>,
+        gas: Option<u64>,
     ) -> Self {
         let memo = memo.unwrap_or_default();
         Self {
@@ -46,7 +63,7 @@ where
             gas,
             memo,
             key_service,
-            chain_id,
+            network,
             sequence: 0,
             account_number: 0,
             messages: vec![],
@@ -65,6 +82,16 @@ where
         self
     }
 
+    /// chain id the transactions are signed for, the one of the builder's network
+    pub fn chain_id(&self) -> &str {
+        &self.network.chain_id
+    }
+
+    /// bech32 account address of the signer on the builder's network
+    pub fn sender_address(&self) -> Result<Address, Error> {
+        self.key_service.account_address(&self.network)
+    }
+
     /// add tx message to be executed
     pub fn add_message(&mut self, msg: M) -> &mut Self {
         self.messages.push(msg);
@@ -73,14 +100,13 @@ where
 
     #[inline]
     fn get_fee(&self) -> Fee {
-        let amount = if self.fee.is_some() {
-            vec![self.fee.clone().unwrap()]
-        } else {
-            vec![]
-        };
         Fee {
             gas: self.gas.unwrap_or(20000),
-            amount,
+            amount: self
+                .fee
+                .iter()
+                .map(|fee| fee.on_network(&self.network))
+                .collect(),
         }
     }
 
@@ -89,7 +115,7 @@ where
         let sign_doc = SignDoc {
             account_number: self.account_number,
             sequence: self.sequence,
-            chain_id: self.chain_id.clone(),
+            chain_id: self.network.chain_id.clone(),
             memo: self.memo.clone(),
             fee,
             msgs: self.messages.clone(),
@@ -125,20 +151,55 @@ where
     }
 }
 
+/// signed multi-send payout batch
+pub type AminoPayoutBatch = PayoutBatch<(Address, Amount), Transaction<MultiSend>>;
+
+impl<T> TxBuilder<T, MultiSend>
+where
+    T: KeyService + Clone,
+{
+    /// split payouts into multi-send transactions of at most `max_outputs` outputs,
+    /// signed with sequential sequence numbers starting from the current one
+    pub async fn build_payouts(
+        &mut self,
+        payouts: &[(Address, Amount)],
+        max_outputs: usize,
+        sync_mode: SyncMode,
+    ) -> Result<Vec<AminoPayoutBatch>, Error> {
+        let from_address = self.sender_address()?;
+        for (address, _) in payouts {
+            self.network.check_address(address)?;
+        }
+        let mut batches = vec![];
+        for chunk in split_payouts(payouts, max_outputs)? {
+            let msg = MultiSend::from_network_payouts(&self.network, &from_address, &chunk)?;
+            let mut builder = self.clone();
+            builder.messages = vec![msg];
+            let tx = builder.build(sync_mode.clone()).await?;
+            batches.push(PayoutBatch {
+                sequence: self.sequence,
+                payouts: chunk,
+                tx,
+            });
+            self.sequence += 1;
+        }
+        Ok(batches)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::constant::ACCOUNT_ADDRESS_PREFIX;
     use crate::hd_wallet::mnemonic::Mnemonic;
     use crate::key_service::private_key_service::PrivateKeyService;
-    use crate::message::{Transfer, TransferValue};
+    use crate::message::{AnyMessage, MultiSendEntry, Transfer, TransferValue};
+    use crate::types::address::AddressKind;
     use crate::types::basic::{Amount, Denom};
     use crate::types::key::PublicKey;
-    use stdtx::Address;
 
     #[tokio::test]
     async fn test_tx_builder() {
-        let fee = Amount::new(100000, Denom::Basecro);
+        let fee = Amount::try_new(100000, Denom::Basecro).unwrap();
         let gas = Some(300000);
         let memo = None;
         let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
@@ -146,11 +207,12 @@ mod test {
         let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
         let chain_id = "test".to_string();
         let mut builder = TxBuilder::new(key_service, chain_id, memo, Some(fee.clone()), gas);
-        let (_, to_address) =
+        assert_eq!(builder.chain_id(), "test");
+        let to_address =
             Address::from_bech32("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd").unwrap();
-        let from_address = builder.key_service.address().unwrap();
-        let amount = Amount::new(100000000, Denom::Basecro);
-        let msg = Transfer::new(from_address, to_address, amount);
+        let from_address = builder.sender_address().unwrap();
+        let amount = Amount::try_new(100000000, Denom::Basecro).unwrap();
+        let msg = Transfer::new(&from_address, &to_address, amount).unwrap();
         builder.add_message(msg);
         let account_number = 0;
         let sequence = 0;
@@ -175,8 +237,8 @@ mod test {
                         transfer_type: "cosmos-sdk/MsgSend".into(),
                         value: TransferValue {
                             from_address: "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf".into(),
-                            to_address: to_address.to_bech32(ACCOUNT_ADDRESS_PREFIX),
-                            amount: vec![Amount::new(100000000, Denom::Basecro)],
+                            to_address: to_address.to_string(),
+                            amount: vec![Amount::try_new(100000000, Denom::Basecro).unwrap()],
                         }
                     }
                 ]
@@ -185,4 +247,160 @@ mod test {
         };
         assert_eq!(transfer, transfer_expected);
     }
+
+    #[tokio::test]
+    async fn test_build_payouts() {
+        let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
+        let mnemonic = Mnemonic::from_str(words, None).unwrap();
+        let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
+        let mut builder: TxBuilder<_, MultiSend> =
+            TxBuilder::new(key_service, "test".to_string(), None, None, Some(300000));
+        builder.set_account_number(9).set_sequence(5);
+        let to_address =
+            Address::from_bech32("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd").unwrap();
+        let payouts = vec![
+            (to_address.clone(), Amount::try_new(1, Denom::Cro).unwrap()),
+            (
+                to_address.clone(),
+                Amount::try_new(200, Denom::Basecro).unwrap(),
+            ),
+            (
+                to_address.clone(),
+                Amount::try_new(300, Denom::Basecro).unwrap(),
+            ),
+        ];
+        let batches = builder
+            .build_payouts(&payouts, 2, SyncMode::Sync)
+            .await
+            .unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].sequence, 5);
+        assert_eq!(batches[0].payouts.len(), 2);
+        assert_eq!(batches[1].sequence, 6);
+        assert_eq!(batches[1].tx.tx.signatures[0].sequence, 6);
+        assert_eq!(builder.sequence, 7);
+
+        let value = &batches[0].tx.tx.messages[0].value;
+        assert_eq!(value.inputs.len(), 1);
+        assert_eq!(
+            value.inputs[0].coins,
+            vec![Amount::try_new(100000200, Denom::Basecro).unwrap()]
+        );
+        assert_eq!(value.outputs.len(), 2);
+
+        // inputs and outputs must match
+        let from_address = builder.sender_address().unwrap();
+        let input = MultiSendEntry::new(
+            &from_address,
+            vec![Amount::try_new(100, Denom::Basecro).unwrap()],
+        )
+        .unwrap();
+        let output = MultiSendEntry::new(
+            &to_address,
+            vec![Amount::try_new(99, Denom::Basecro).unwrap()],
+        )
+        .unwrap();
+        let validator = MultiSendEntry::new(&to_address.to_validator(), vec![]);
+        assert!(validator.is_err());
+        assert!(MultiSend::new(vec![input], vec![output]).is_err());
+        assert!(builder
+            .build_payouts(&payouts, 0, SyncMode::Sync)
+            .await
+            .is_err());
+
+        // receivers must belong to the builder's network
+        let testnet_address = Network::testnet().address(AddressKind::Account, to_address.raw());
+        assert!(builder
+            .build_payouts(
+                &[(testnet_address, Amount::try_new(1, Denom::Cro).unwrap())],
+                2,
+                SyncMode::Sync
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_testnet_denom() {
+        let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
+        let mnemonic = Mnemonic::from_str(words, None).unwrap();
+        let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
+        let network = Network::testnet();
+        let fee = Amount::try_new(100000, Denom::Basecro).unwrap();
+        let mut builder: TxBuilder<_, Transfer> =
+            TxBuilder::with_network(key_service, network.clone(), None, Some(fee), None);
+        let from_address = builder.sender_address().unwrap();
+        let to_address = network.address(AddressKind::Account, from_address.raw());
+        let amount = Amount::try_new(1, Denom::Cro).unwrap();
+        let msg = Transfer::with_network(&network, &from_address, &to_address, amount).unwrap();
+        assert_eq!(msg.value.amount[0].denom(), "basetcro");
+        assert_eq!(msg.value.amount[0].amount(), 100000000);
+        let mainnet_address = Network::mainnet().address(AddressKind::Account, to_address.raw());
+        assert!(Transfer::with_network(
+            &network,
+            &from_address,
+            &mainnet_address,
+            Amount::try_new(1, Denom::Cro).unwrap()
+        )
+        .is_err());
+
+        builder.add_message(msg);
+        let tx = builder.build(SyncMode::Sync).await.unwrap();
+        assert_eq!(tx.tx.fee.amount[0].denom(), "basetcro");
+        assert_eq!(tx.tx.fee.amount[0].amount(), 100000);
+
+        let mut builder: TxBuilder<_, MultiSend> =
+            TxBuilder::with_network(builder.key_service.clone(), network, None, None, None);
+        let payouts = vec![(to_address, Amount::try_new(2, Denom::Basecro).unwrap())];
+        let batches = builder
+            .build_payouts(&payouts, 1, SyncMode::Sync)
+            .await
+            .unwrap();
+        let value = &batches[0].tx.tx.messages[0].value;
+        assert_eq!(value.inputs[0].coins[0].denom(), "basetcro");
+        assert_eq!(value.outputs[0].coins[0].denom(), "basetcro");
+    }
+
+    #[tokio::test]
+    async fn test_deserialize_and_verify() {
+        let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
+        let mnemonic = Mnemonic::from_str(words, None).unwrap();
+        let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
+        let fee = Amount::try_new(100000, Denom::Basecro).unwrap();
+        let mut builder = TxBuilder::new(
+            key_service,
+            "test".to_string(),
+            None,
+            Some(fee),
+            Some(300000),
+        );
+        let to_address =
+            Address::from_bech32("cro1s2gsnugjhpzac8m7necv3527jp28z9w002najd").unwrap();
+        let from_address = builder.sender_address().unwrap();
+        let msg = Transfer::new(
+            &from_address,
+            &to_address,
+            Amount::try_new(1, Denom::Cro).unwrap(),
+        )
+        .unwrap();
+        builder
+            .add_message(msg)
+            .set_account_number(9)
+            .set_sequence(4);
+        let transaction = builder.build(SyncMode::Block).await.unwrap();
+        let json = serde_json::to_string(&transaction).unwrap();
+
+        let parsed: Transaction<Transfer> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, transaction);
+
+        let parsed: Transaction<AnyMessage> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&transaction).unwrap()
+        );
+        parsed.tx.verify("test", 9, 4).unwrap();
+
+        assert!(parsed.tx.verify("test", 9, 5).is_err());
+        assert!(parsed.tx.verify("mainnet", 9, 4).is_err());
+    }
 }
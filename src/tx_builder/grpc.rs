@@ -1,24 +1,50 @@
-use crate::constant::ACCOUNT_ADDRESS_PREFIX;
+use crate::client::Client;
+use crate::constant::DEFAULT_GAS_ADJUSTMENT;
 use crate::error::Error;
 use crate::key_service::KeyService;
 use crate::message::Msg;
-use crate::proto::cosmos::bank::v1beta1::MsgSend;
+use crate::network::Network;
+use crate::proto::cosmos::bank::v1beta1::{Input, MsgMultiSend, MsgSend, Output};
 use crate::proto::cosmos::base::v1beta1::Coin;
 use crate::proto::cosmos::tx::v1beta1::{
     mode_info, AuthInfo, Fee, ModeInfo, SignDoc, SignerInfo, TxBody, TxRaw,
 };
+use crate::tx_builder::payout::{split_payouts, PayoutBatch};
+use crate::types::address::Address;
+use std::collections::BTreeMap;
 
 /// struct for building signed transactions
+#[derive(Clone)]
 pub struct TxBuilder<T: KeyService + Clone> {
     /// signing backend
     pub key_service: T,
-    chain_id: String,
+    network: Network,
     messages: Vec<Msg>,
     memo: Option<String>,
     timeout_height: u64,
     account_number: u64,
     sequence: u64,
     fee: Option<Fee>,
+    gas_adjustment: f64,
+}
+
+/// signed multi-send payout batch, the transaction is base64 encoded
+pub type GrpcPayoutBatch = PayoutBatch<(Address, Coin), String>;
+
+/// sum up coins per denomination
+fn sum_coins<'a>(coins: impl Iterator<Item = &'a Coin>) -> Result<BTreeMap<String, u128>, Error> {
+    let mut total = BTreeMap::new();
+    for coin in coins {
+        let amount: u128 = coin
+            .amount
+            .parse()
+            .map_err(|_| Error::InputError(format!("invalid coin amount: {}", coin.amount)))?;
+        let sum: &mut u128 = total.entry(coin.denom.clone()).or_default();
+        *sum = sum
+            .checked_add(amount)
+            .ok_or_else(|| Error::InputError("amount overflow".to_string()))?;
+    }
+    Ok(total)
 }
 
 fn encode<T: prost::Message>(msg: &T) -> Result<Vec<u8>, Error> {
@@ -28,23 +54,37 @@ fn encode<T: prost::Message>(msg: &T) -> Result<Vec<u8>, Error> {
 }
 
 impl<T: KeyService + Clone> TxBuilder<T> {
-    /// instatiate new tx builder
+    /// instatiate new tx builder for a mainnet-like network with the chain id `chain_id`
     pub fn new(
         key_service: T,
         chain_id: String,
         memo: Option<String>,
         timeout_height: u64,
         fee: Option<Fee>,
+    ) -> Self {
+        let mut network = Network::mainnet();
+        network.set_chain_id(chain_id);
+        Self::with_network(key_service, network, memo, timeout_height, fee)
+    }
+
+    /// instatiate new tx builder for `network`
+    pub fn with_network(
+        key_service: T,
+        network: Network,
+        memo: Option<String>,
+        timeout_height: u64,
+        fee: Option<Fee>,
     ) -> Self {
         Self {
             key_service,
             messages: vec![],
-            chain_id,
+            network,
             memo,
             timeout_height,
             account_number: 0,
             sequence: 0,
             fee,
+            gas_adjustment: DEFAULT_GAS_ADJUSTMENT,
         }
     }
 
@@ -60,6 +100,63 @@ impl<T: KeyService + Clone> TxBuilder<T> {
         self
     }
 
+    /// set the fee and gas limit
+    pub fn set_fee(&mut self, fee: Fee) -> &mut Self {
+        self.fee = Some(fee);
+        self
+    }
+
+    /// set the multiplier applied to simulated gas, e.g. 1.3 for 30% headroom
+    pub fn set_gas_adjustment(&mut self, gas_adjustment: f64) -> &mut Self {
+        self.gas_adjustment = gas_adjustment;
+        self
+    }
+
+    /// set the gas price in base denomination per gas unit used for estimated fees
+    pub fn set_gas_price(&mut self, gas_price: f64) -> &mut Self {
+        self.network.set_gas_price(gas_price);
+        self
+    }
+
+    /// set the gas limit to the adjusted `gas_used` and the fee to gas limit times gas price,
+    /// the fee payer and granter are kept
+    pub fn set_gas_used(&mut self, gas_used: u64) -> &mut Self {
+        let gas_limit = (gas_used as f64 * self.gas_adjustment).ceil() as u64;
+        let amount = self.network.fee_amount(gas_limit);
+        let fee = self.fee.take().unwrap_or_default();
+        self.fee = Some(Fee {
+            amount: vec![self.base_coin(amount)],
+            gas_limit,
+            ..fee
+        });
+        self
+    }
+
+    /// simulate the transaction with `client` and set the gas limit and fee from the gas used
+    pub async fn estimate_fee(&mut self, client: &Client) -> Result<Fee, Error> {
+        let gas_info = client.simulate(self.simulation_tx()?).await?;
+        self.set_gas_used(gas_info.gas_used);
+        Ok(self.fee.clone().unwrap_or_default())
+    }
+
+    /// network the transactions are signed for
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// bech32 account address of the signer on the builder's network
+    pub fn sender_address(&self) -> Result<Address, Error> {
+        self.key_service.account_address(&self.network)
+    }
+
+    /// coin of `amount` in the base denomination of the builder's network
+    pub fn base_coin(&self, amount: u128) -> Coin {
+        Coin {
+            denom: self.network.base_denom.clone(),
+            amount: amount.to_string(),
+        }
+    }
+
     /// add a message to execute
     pub fn add_message(&mut self, msg: Msg) -> &mut Self {
         self.messages.push(msg);
@@ -116,12 +213,12 @@ impl<T: KeyService + Clone> TxBuilder<T> {
     }
 
     /// create a MsgSend type message
-    pub fn create_msg(&self, to_address: String, amount: Coin) -> Result<Msg, Error> {
-        let from_address = self.key_service.address()?;
-        let address_str = from_address.to_bech32(ACCOUNT_ADDRESS_PREFIX);
+    pub fn create_msg(&self, to_address: &Address, amount: Coin) -> Result<Msg, Error> {
+        let from_address = self.sender_address()?;
+        self.network.check_address(to_address)?;
         let msg = MsgSend {
-            from_address: address_str,
-            to_address,
+            from_address: from_address.to_string(),
+            to_address: to_address.account_bech32()?,
             amount: vec![amount],
         };
         let buf = encode(&msg)?;
@@ -132,6 +229,83 @@ impl<T: KeyService + Clone> TxBuilder<T> {
         Ok(Msg::from(any))
     }
 
+    /// create a MsgMultiSend type message, the sum of inputs must equal the sum of outputs
+    pub fn create_multi_send_msg(
+        &self,
+        inputs: Vec<Input>,
+        outputs: Vec<Output>,
+    ) -> Result<Msg, Error> {
+        if inputs.is_empty() || outputs.is_empty() {
+            return Err(Error::InputError(
+                "multi-send requires at least one input and one output".to_string(),
+            ));
+        }
+        let input_sum = sum_coins(inputs.iter().flat_map(|i| i.coins.iter()))?;
+        let output_sum = sum_coins(outputs.iter().flat_map(|o| o.coins.iter()))?;
+        if input_sum != output_sum {
+            return Err(Error::InputError(
+                "sum of multi-send inputs and outputs mismatch".to_string(),
+            ));
+        }
+        let msg = MsgMultiSend { inputs, outputs };
+        let buf = encode(&msg)?;
+        let any = prost_types::Any {
+            type_url: "/cosmos.bank.v1beta1.MsgMultiSend".to_string(),
+            value: buf,
+        };
+        Ok(Msg::from(any))
+    }
+
+    /// create a MsgMultiSend type message paying `payouts` from the signer's address
+    pub fn create_payout_msg(&self, payouts: &[(Address, Coin)]) -> Result<Msg, Error> {
+        let from_address = self.sender_address()?;
+        let total = sum_coins(payouts.iter().map(|(_, coin)| coin))?;
+        let input = Input {
+            address: from_address.to_string(),
+            coins: total
+                .into_iter()
+                .map(|(denom, amount)| Coin {
+                    denom,
+                    amount: amount.to_string(),
+                })
+                .collect(),
+        };
+        let outputs = payouts
+            .iter()
+            .map(|(address, coin)| {
+                self.network.check_address(address)?;
+                Ok(Output {
+                    address: address.account_bech32()?,
+                    coins: vec![coin.clone()],
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        self.create_multi_send_msg(vec![input], outputs)
+    }
+
+    /// split payouts into multi-send transactions of at most `max_outputs` outputs,
+    /// signed with sequential sequence numbers starting from the current one
+    pub async fn build_payouts(
+        &mut self,
+        payouts: &[(Address, Coin)],
+        max_outputs: usize,
+    ) -> Result<Vec<GrpcPayoutBatch>, Error> {
+        let mut batches = vec![];
+        for chunk in split_payouts(payouts, max_outputs)? {
+            let msg = self.create_payout_msg(&chunk)?;
+            let mut builder = self.clone();
+            builder.messages = vec![msg];
+            let tx = builder.build().await?;
+            batches.push(PayoutBatch {
+                sequence: self.sequence,
+                payouts: chunk,
+                tx,
+            });
+            self.sequence += 1;
+        }
+        Ok(batches)
+    }
+
     /// get signing payload
     pub fn sign_doc(&self) -> Result<SignDoc, Error> {
         let body_bytes = self.raw_tx_body()?;
@@ -139,28 +313,39 @@ impl<T: KeyService + Clone> TxBuilder<T> {
         let sign_doc = SignDoc {
             body_bytes,
             auth_info_bytes,
-            chain_id: self.chain_id.clone(),
+            chain_id: self.network.chain_id.clone(),
             account_number: self.account_number,
         };
         Ok(sign_doc)
     }
 
-    /// build a tx
-    pub async fn build(&self) -> Result<String, Error> {
+    fn tx_raw(&self, signature: Vec<u8>) -> Result<TxRaw, Error> {
+        Ok(TxRaw {
+            body_bytes: self.raw_tx_body()?,
+            auth_info_bytes: encode(&self.auth_info()?)?,
+            signatures: vec![signature],
+        })
+    }
+
+    /// unsigned tx bytes for gas simulation, signatures are not verified when simulating
+    pub fn simulation_tx(&self) -> Result<Vec<u8>, Error> {
+        encode(&self.tx_raw(vec![])?)
+    }
+
+    /// build a tx, returns the `TxRaw` bytes which `utils::codec::tx_hash` computes the hash of
+    pub async fn build_raw(&self) -> Result<Vec<u8>, Error> {
         let sign_doc = self.sign_doc()?;
         let signdoc_buf = encode(&sign_doc)?;
         let signature_base64 = self.key_service.sign(&signdoc_buf).await?;
         let signature = base64::decode(signature_base64).map_err(|e| {
             Error::SerializeError(format!("invalid base64 signature, decode error: {:?}", e))
         })?;
-        let body_bytes = self.raw_tx_body()?;
-        let auth_info_bytes = encode(&self.auth_info()?)?;
-        let tx_raw = TxRaw {
-            body_bytes,
-            auth_info_bytes,
-            signatures: vec![signature],
-        };
-        let bytes = encode(&tx_raw)?;
+        encode(&self.tx_raw(signature)?)
+    }
+
+    /// build a tx, returns the base64 encoded `TxRaw` bytes
+    pub async fn build(&self) -> Result<String, Error> {
+        let bytes = self.build_raw().await?;
         let string_b64 = base64::encode(bytes);
         Ok(string_b64)
     }
@@ -171,6 +356,8 @@ mod test {
     use super::*;
     use crate::hd_wallet::mnemonic::Mnemonic;
     use crate::key_service::private_key_service::PrivateKeyService;
+    use crate::types::address::AddressKind;
+    use crate::utils::codec::tx_hash;
     use prost::Message;
 
     #[tokio::test]
@@ -218,12 +405,13 @@ mod test {
         assert_eq!(auth_info, AuthInfo::decode(&*auth_info_bytes).unwrap());
 
         // add msg
-        let to_address = "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9".into();
-        let amount = Coin {
-            denom: "basecro".into(),
-            amount: 100000000.to_string(),
-        };
-        let msg = builder.create_msg(to_address, amount).unwrap();
+        let to_address: Address = "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9"
+            .parse()
+            .unwrap();
+        let amount = builder.base_coin(100000000);
+        let msg = builder.create_msg(&to_address, amount.clone()).unwrap();
+        let testnet_address = Network::testnet().address(AddressKind::Account, to_address.raw());
+        assert!(builder.create_msg(&testnet_address, amount).is_err());
         builder.add_message(msg);
 
         // test signature
@@ -236,5 +424,35 @@ mod test {
         let tx = builder.build().await.unwrap();
         let tx_expect = "CpMBCo4BChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEm4KKmNybzF1OXE4bWZwemh5djJzNDNqczdsNXFzZWFweDVrdDNnMnJmN3BwZhIqY3JvMWZqNmpwbXV5a3ZyYTRreHJ3MGNwMjBlNHZ4NHI4ZWRhOHEzeW45GhQKB2Jhc2Vjcm8SCTEwMDAwMDAwMBgBEmoKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJ7S/lMTMifTWfQxi6dr+2t3ZUsYjVzGiLcjl0kcPImIhIECgIIARgEEhYKEAoHYmFzZWNybxIFMTAwMDAQ4KcSGkCOWoGjmfFFurZEhimOjj6CN68EQMAvD4iOaoQ+7iKB8iAPyXj8JHe+Z60e+GnJ5Bphn9skl96FPpn9GnGh8mfE";
         assert_eq!(tx, tx_expect);
+
+        // test tx hash
+        let tx_raw = builder.build_raw().await.unwrap();
+        assert_eq!(
+            tx_hash(&tx_raw),
+            "BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D"
+        );
+    }
+
+    #[test]
+    fn test_set_gas_used() {
+        let words = "dune car envelope chuckle elbow slight proud fury remove candy uphold puzzle call select sibling sport gadget please want vault glance verb damage gown";
+        let mnemonic = Mnemonic::from_str(words, None).unwrap();
+        let key_service = PrivateKeyService::new_from_mnemonic(mnemonic).unwrap();
+        let fee = Fee {
+            granter: "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9".to_string(),
+            ..Default::default()
+        };
+        let mut builder = TxBuilder::new(key_service, "test".into(), None, 0, Some(fee));
+        builder.set_gas_adjustment(1.5).set_gas_price(0.1);
+
+        let tx_raw = TxRaw::decode(&*builder.simulation_tx().unwrap()).unwrap();
+        assert_eq!(tx_raw.signatures, vec![Vec::<u8>::new()]);
+
+        builder.set_gas_used(100001);
+        let auth_info = builder.auth_info().unwrap();
+        let fee = auth_info.fee.unwrap();
+        assert_eq!(fee.gas_limit, 150002);
+        assert_eq!(fee.amount, vec![builder.base_coin(15001)]);
+        assert_eq!(fee.granter, "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9");
     }
 }
@@ -7,3 +7,6 @@ pub use amino::*;
 mod grpc;
 #[cfg(feature = "grpc")]
 pub use grpc::*;
+
+/// batch payouts
+pub mod payout;
@@ -0,0 +1,44 @@
+//! Batch payouts signed as multi-send transactions
+
+use crate::error::Error;
+
+/// payouts signed into a single multi-send transaction
+#[derive(Debug, Clone)]
+pub struct PayoutBatch<P, Tx> {
+    /// sequence number the transaction is signed with
+    pub sequence: u64,
+    /// payouts (receiver, amount) included in the transaction
+    pub payouts: Vec<P>,
+    /// signed transaction
+    pub tx: Tx,
+}
+
+/// broadcast outcome of a single payout batch
+#[derive(Debug)]
+pub struct PayoutReport<P, R> {
+    /// sequence number the transaction was signed with
+    pub sequence: u64,
+    /// payouts (receiver, amount) included in the transaction
+    pub payouts: Vec<P>,
+    /// broadcast result, batches after a failed one are not broadcasted
+    pub result: Result<R, Error>,
+}
+
+/// split payouts into chunks of at most `max_outputs` entries
+pub(crate) fn split_payouts<P: Clone>(
+    payouts: &[P],
+    max_outputs: usize,
+) -> Result<Vec<Vec<P>>, Error> {
+    if payouts.is_empty() {
+        return Err(Error::InputError("no payouts given".to_string()));
+    }
+    if max_outputs == 0 {
+        return Err(Error::InputError(
+            "max outputs per transaction must be positive".to_string(),
+        ));
+    }
+    Ok(payouts
+        .chunks(max_outputs)
+        .map(|chunk| chunk.to_vec())
+        .collect())
+}
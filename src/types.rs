@@ -1,8 +1,20 @@
+/// on-chain accounts including module and vesting accounts
+pub mod account;
+/// bech32 account, validator and consensus addresses
+pub mod address;
 /// amount, fee etc.
 pub mod basic;
+/// broadcast results and mapped SDK errors
+pub mod broadcast;
+/// multi-denom coins
+pub mod coin;
+/// denomination metadata and IBC denom traces
+pub mod denom;
 /// private and public key types
 pub mod key;
 /// signature + signdoc
 pub mod signature;
+/// Tendermint RPC responses
+pub mod tendermint;
 /// transaction auxiliary structures
 pub mod transaction;
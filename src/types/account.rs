@@ -0,0 +1,514 @@
+use crate::error::Error;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::auth::v1beta1::{
+    BaseAccount as ProtoBaseAccount, ModuleAccount as ProtoModuleAccount,
+};
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::vesting::v1beta1::{
+    BaseVestingAccount as ProtoBaseVestingAccount, ContinuousVestingAccount, DelayedVestingAccount,
+    PeriodicVestingAccount,
+};
+use crate::types::coin::{Coin, Coins};
+use crate::types::key::PublicKey;
+use crate::utils::codec::serde_from_str;
+use serde::Deserialize;
+#[cfg(feature = "grpc")]
+use std::convert::TryFrom;
+
+/// type url of `BaseAccount`
+pub const BASE_ACCOUNT_TYPE_URL: &str = "/cosmos.auth.v1beta1.BaseAccount";
+/// type url of `ModuleAccount`
+pub const MODULE_ACCOUNT_TYPE_URL: &str = "/cosmos.auth.v1beta1.ModuleAccount";
+/// type url of `ContinuousVestingAccount`
+pub const CONTINUOUS_VESTING_ACCOUNT_TYPE_URL: &str =
+    "/cosmos.vesting.v1beta1.ContinuousVestingAccount";
+/// type url of `DelayedVestingAccount`
+pub const DELAYED_VESTING_ACCOUNT_TYPE_URL: &str = "/cosmos.vesting.v1beta1.DelayedVestingAccount";
+/// type url of `PeriodicVestingAccount`
+pub const PERIODIC_VESTING_ACCOUNT_TYPE_URL: &str =
+    "/cosmos.vesting.v1beta1.PeriodicVestingAccount";
+/// type url of a secp256k1 public key
+const SECP256K1_PUBKEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+/// public key of an account, set once it signed a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountPublicKey {
+    /// secp256k1 key of a regular account
+    Secp256k1(PublicKey),
+    /// other key types, e.g. "/cosmos.crypto.multisig.LegacyAminoPubKey"
+    Other(String),
+}
+
+/// fields shared by all account types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseAccount {
+    /// bech32 address
+    pub address: String,
+    /// on-chain public key
+    pub pub_key: Option<AccountPublicKey>,
+    /// account number
+    pub account_number: u64,
+    /// sequence of the next transaction
+    pub sequence: u64,
+}
+
+/// vesting period of a periodic vesting account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VestingPeriod {
+    /// duration in seconds, starting at the end of the previous period
+    pub length: i64,
+    /// coins vested at the end of the period
+    pub amount: Coins,
+}
+
+/// how the original vesting coins are unlocked until the end time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VestingSchedule {
+    /// linearly from `start_time`
+    Continuous {
+        /// unix time in seconds
+        start_time: i64,
+    },
+    /// all at the end time
+    Delayed,
+    /// at the end of each period from `start_time`
+    Periodic {
+        /// unix time in seconds
+        start_time: i64,
+        /// periods in order
+        periods: Vec<VestingPeriod>,
+    },
+}
+
+/// account whose original coins are locked until vested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VestingAccount {
+    /// base account
+    pub base: BaseAccount,
+    /// coins locked at creation
+    pub original_vesting: Coins,
+    /// delegated coins which were vested at delegation
+    pub delegated_free: Coins,
+    /// delegated coins which were vesting at delegation
+    pub delegated_vesting: Coins,
+    /// unix time in seconds all coins are vested
+    pub end_time: i64,
+    /// unlock schedule
+    pub schedule: VestingSchedule,
+}
+
+impl VestingAccount {
+    /// coins vested at unix time `time`
+    pub fn vested_coins(&self, time: i64) -> Coins {
+        if time >= self.end_time {
+            return self.original_vesting.clone();
+        }
+        match &self.schedule {
+            VestingSchedule::Delayed => Coins::default(),
+            VestingSchedule::Continuous { start_time } => {
+                if time <= *start_time {
+                    return Coins::default();
+                }
+                let elapsed = (time - start_time) as u128;
+                let duration = (self.end_time - start_time) as u128;
+                // amount * elapsed / duration without overflowing, truncated
+                let coins = self
+                    .original_vesting
+                    .iter()
+                    .filter_map(|coin| {
+                        let amount = coin.amount();
+                        let vested =
+                            amount / duration * elapsed + amount % duration * elapsed / duration;
+                        Coin::new(vested, coin.denom()).ok()
+                    })
+                    .collect();
+                Coins::new(coins).unwrap_or_default()
+            }
+            VestingSchedule::Periodic {
+                start_time,
+                periods,
+            } => {
+                let mut vested = Coins::default();
+                let mut period_end = *start_time;
+                for period in periods {
+                    period_end += period.length;
+                    if time < period_end {
+                        break;
+                    }
+                    vested = vested.checked_add(&period.amount).unwrap_or(vested);
+                }
+                vested
+            }
+        }
+    }
+
+    /// coins still vesting at unix time `time`
+    pub fn vesting_coins(&self, time: i64) -> Coins {
+        self.original_vesting
+            .saturating_sub(&self.vested_coins(time))
+    }
+
+    /// vesting coins at unix time `time` which are not delegated and can't be spent
+    pub fn locked_coins(&self, time: i64) -> Coins {
+        self.vesting_coins(time)
+            .saturating_sub(&self.delegated_vesting)
+    }
+}
+
+/// on-chain account of `/cosmos.auth.v1beta1.Query/Account`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Account {
+    /// regular account
+    Base(BaseAccount),
+    /// account of a module, e.g. "distribution"
+    Module {
+        /// base account
+        base: BaseAccount,
+        /// module name
+        name: String,
+        /// permissions, e.g. "minter" or "burner"
+        permissions: Vec<String>,
+    },
+    /// continuous, delayed or periodic vesting account
+    Vesting(VestingAccount),
+}
+
+impl Account {
+    /// fields shared by all account types
+    pub fn base(&self) -> &BaseAccount {
+        match self {
+            Account::Base(base) => base,
+            Account::Module { base, .. } => base,
+            Account::Vesting(vesting) => &vesting.base,
+        }
+    }
+
+    /// vesting details of a vesting account
+    pub fn vesting(&self) -> Option<&VestingAccount> {
+        match self {
+            Account::Vesting(vesting) => Some(vesting),
+            _ => None,
+        }
+    }
+
+    /// coins which can't be spent at unix time `time`, empty for non-vesting accounts
+    pub fn locked_coins(&self, time: i64) -> Coins {
+        self.vesting()
+            .map(|vesting| vesting.locked_coins(time))
+            .unwrap_or_default()
+    }
+
+    /// coins of `balances` which can be spent at unix time `time`
+    pub fn spendable_coins(&self, balances: &Coins, time: i64) -> Coins {
+        balances.saturating_sub(&self.locked_coins(time))
+    }
+
+    /// decode the `account` of a REST account response
+    pub fn from_json(account: &serde_json::Value) -> Result<Self, Error> {
+        // {'@type': '/cosmos.vesting.v1beta1.ContinuousVestingAccount', 'base_vesting_account': {'base_account': {...}, 'original_vesting': [...], 'delegated_free': [], 'delegated_vesting': [], 'end_time': '1700000000'}, 'start_time': '1600000000'}
+        let invalid = |e: String| Error::ClientError(format!("invalid account {}: {}", account, e));
+        let json: JsonAccount =
+            serde_json::from_value(account.clone()).map_err(|e| invalid(e.to_string()))?;
+        let vesting = |schedule| -> Result<Self, Error> {
+            let vesting = json
+                .base_vesting_account
+                .clone()
+                .ok_or_else(|| invalid("missing base_vesting_account".to_string()))?;
+            Ok(Account::Vesting(VestingAccount {
+                base: vesting.base_account.try_into_base()?,
+                original_vesting: vesting.original_vesting,
+                delegated_free: vesting.delegated_free,
+                delegated_vesting: vesting.delegated_vesting,
+                end_time: vesting.end_time,
+                schedule,
+            }))
+        };
+        match json.kind.as_str() {
+            BASE_ACCOUNT_TYPE_URL => Ok(Account::Base(json.base.try_into_base()?)),
+            MODULE_ACCOUNT_TYPE_URL => {
+                let base = json
+                    .base_account
+                    .clone()
+                    .ok_or_else(|| invalid("missing base_account".to_string()))?;
+                Ok(Account::Module {
+                    base: base.try_into_base()?,
+                    name: json.name.clone(),
+                    permissions: json.permissions.clone(),
+                })
+            }
+            CONTINUOUS_VESTING_ACCOUNT_TYPE_URL => vesting(VestingSchedule::Continuous {
+                start_time: json.start_time,
+            }),
+            DELAYED_VESTING_ACCOUNT_TYPE_URL => vesting(VestingSchedule::Delayed),
+            PERIODIC_VESTING_ACCOUNT_TYPE_URL => vesting(VestingSchedule::Periodic {
+                start_time: json.start_time,
+                periods: json
+                    .vesting_periods
+                    .iter()
+                    .map(|period| VestingPeriod {
+                        length: period.length,
+                        amount: period.amount.clone(),
+                    })
+                    .collect(),
+            }),
+            kind => Err(invalid(format!("unsupported account type {}", kind))),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+struct JsonPubKey {
+    #[serde(rename = "@type")]
+    kind: String,
+    #[serde(default)]
+    key: String,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+struct JsonBaseAccount {
+    address: String,
+    pub_key: Option<JsonPubKey>,
+    account_number: Option<String>,
+    sequence: Option<String>,
+}
+
+impl JsonBaseAccount {
+    fn try_into_base(self) -> Result<BaseAccount, Error> {
+        let number = |value: Option<String>, name: &str| {
+            value
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| Error::ClientError(format!("invalid {} of account", name)))
+        };
+        let pub_key = match self.pub_key {
+            Some(key) if key.kind == SECP256K1_PUBKEY_TYPE_URL => Some(
+                AccountPublicKey::Secp256k1(PublicKey::from_base64_str(&key.key)?),
+            ),
+            Some(key) => Some(AccountPublicKey::Other(key.kind)),
+            None => None,
+        };
+        Ok(BaseAccount {
+            address: self.address,
+            pub_key,
+            account_number: number(self.account_number, "account number")?,
+            sequence: number(self.sequence, "sequence")?,
+        })
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+struct JsonBaseVestingAccount {
+    base_account: JsonBaseAccount,
+    original_vesting: Coins,
+    delegated_free: Coins,
+    delegated_vesting: Coins,
+    #[serde(deserialize_with = "serde_from_str")]
+    end_time: i64,
+}
+
+#[derive(Deserialize, Clone)]
+struct JsonPeriod {
+    #[serde(deserialize_with = "serde_from_str")]
+    length: i64,
+    amount: Coins,
+}
+
+#[derive(Deserialize)]
+struct JsonAccount {
+    #[serde(rename = "@type")]
+    kind: String,
+    #[serde(flatten)]
+    base: JsonBaseAccount,
+    base_account: Option<JsonBaseAccount>,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    permissions: Vec<String>,
+    base_vesting_account: Option<JsonBaseVestingAccount>,
+    #[serde(default, deserialize_with = "serde_from_str_or_default")]
+    start_time: i64,
+    #[serde(default)]
+    vesting_periods: Vec<JsonPeriod>,
+}
+
+fn serde_from_str_or_default<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    value
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .unwrap_or(Ok(0))
+}
+
+#[cfg(feature = "grpc")]
+fn decode<T: prost::Message + Default>(value: &[u8]) -> Result<T, Error> {
+    Ok(T::decode(value)?)
+}
+
+#[cfg(feature = "grpc")]
+impl TryFrom<ProtoBaseAccount> for BaseAccount {
+    type Error = Error;
+
+    fn try_from(account: ProtoBaseAccount) -> Result<Self, Self::Error> {
+        let pub_key = match account.pub_key {
+            Some(key) if key.type_url == SECP256K1_PUBKEY_TYPE_URL => {
+                // `PubKey { key: bytes = 1 }` is encoded like `BytesValue`
+                let raw: Vec<u8> = decode(&key.value)?;
+                let key = hdwallet::secp256k1::PublicKey::from_slice(&raw)?;
+                Some(AccountPublicKey::Secp256k1(key.into()))
+            }
+            Some(key) => Some(AccountPublicKey::Other(key.type_url)),
+            None => None,
+        };
+        Ok(Self {
+            address: account.address,
+            pub_key,
+            account_number: account.account_number,
+            sequence: account.sequence,
+        })
+    }
+}
+
+#[cfg(feature = "grpc")]
+fn vesting_account(
+    account: Option<ProtoBaseVestingAccount>,
+    schedule: VestingSchedule,
+) -> Result<Account, Error> {
+    let account = account.unwrap_or_default();
+    Ok(Account::Vesting(VestingAccount {
+        base: BaseAccount::try_from(account.base_account.unwrap_or_default())?,
+        original_vesting: Coins::try_from(account.original_vesting)?,
+        delegated_free: Coins::try_from(account.delegated_free)?,
+        delegated_vesting: Coins::try_from(account.delegated_vesting)?,
+        end_time: account.end_time,
+        schedule,
+    }))
+}
+
+#[cfg(feature = "grpc")]
+impl TryFrom<prost_types::Any> for Account {
+    type Error = Error;
+
+    fn try_from(any: prost_types::Any) -> Result<Self, Self::Error> {
+        match any.type_url.as_str() {
+            BASE_ACCOUNT_TYPE_URL => Ok(Account::Base(BaseAccount::try_from(decode::<
+                ProtoBaseAccount,
+            >(
+                &any.value
+            )?)?)),
+            MODULE_ACCOUNT_TYPE_URL => {
+                let account: ProtoModuleAccount = decode(&any.value)?;
+                Ok(Account::Module {
+                    base: BaseAccount::try_from(account.base_account.unwrap_or_default())?,
+                    name: account.name,
+                    permissions: account.permissions,
+                })
+            }
+            CONTINUOUS_VESTING_ACCOUNT_TYPE_URL => {
+                let account: ContinuousVestingAccount = decode(&any.value)?;
+                vesting_account(
+                    account.base_vesting_account,
+                    VestingSchedule::Continuous {
+                        start_time: account.start_time,
+                    },
+                )
+            }
+            DELAYED_VESTING_ACCOUNT_TYPE_URL => {
+                let account: DelayedVestingAccount = decode(&any.value)?;
+                vesting_account(account.base_vesting_account, VestingSchedule::Delayed)
+            }
+            PERIODIC_VESTING_ACCOUNT_TYPE_URL => {
+                let account: PeriodicVestingAccount = decode(&any.value)?;
+                let periods = account
+                    .vesting_periods
+                    .into_iter()
+                    .map(|period| {
+                        Ok(VestingPeriod {
+                            length: period.length,
+                            amount: Coins::try_from(period.amount)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                vesting_account(
+                    account.base_vesting_account,
+                    VestingSchedule::Periodic {
+                        start_time: account.start_time,
+                        periods,
+                    },
+                )
+            }
+            kind => Err(Error::ClientError(format!(
+                "unsupported account type {}",
+                kind
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BASE: &str = r#"{"address":"cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf","pub_key":{"@type":"/cosmos.crypto.secp256k1.PubKey","key":"AntL+UxMyJ9NZ9DGLp2v7a3dlSxiNXMaItyOXSRw8iYi"},"account_number":"9","sequence":"25"}"#;
+
+    fn coins(s: &str) -> Coins {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_base_and_module_account() {
+        let json = BASE.replacen('{', r#"{"@type":"/cosmos.auth.v1beta1.BaseAccount","#, 1);
+        let account = Account::from_json(&serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(account.base().account_number, 9);
+        assert_eq!(account.base().sequence, 25);
+        assert!(matches!(
+            account.base().pub_key,
+            Some(AccountPublicKey::Secp256k1(_))
+        ));
+        assert!(account.vesting().is_none());
+        assert!(account.locked_coins(0).is_empty());
+
+        let json = r#"{"@type":"/cosmos.auth.v1beta1.ModuleAccount","base_account":{"address":"cro1jv65s3grqf6v6jl3dp4t6c9t9rk99cd8lyv94w","pub_key":null,"account_number":"4","sequence":"0"},"name":"distribution","permissions":[]}"#;
+        let account = Account::from_json(&serde_json::from_str(json).unwrap()).unwrap();
+        assert!(matches!(&account, Account::Module { name, .. } if name == "distribution"));
+        assert_eq!(account.base().pub_key, None);
+    }
+
+    #[test]
+    fn test_vesting_accounts() {
+        let json = format!(
+            r#"{{"@type":"/cosmos.vesting.v1beta1.ContinuousVestingAccount","base_vesting_account":{{"base_account":{},"original_vesting":[{{"denom":"basecro","amount":"1000"}}],"delegated_free":[],"delegated_vesting":[{{"denom":"basecro","amount":"100"}}],"end_time":"2000"}},"start_time":"1000"}}"#,
+            BASE
+        );
+        let account = Account::from_json(&serde_json::from_str(&json).unwrap()).unwrap();
+        let vesting = account.vesting().unwrap();
+        assert_eq!(vesting.vested_coins(500), Coins::default());
+        assert_eq!(vesting.vested_coins(1250), coins("250basecro"));
+        assert_eq!(vesting.vested_coins(3000), coins("1000basecro"));
+        assert_eq!(account.locked_coins(1250), coins("650basecro"));
+        assert_eq!(
+            account.spendable_coins(&coins("1000basecro"), 1250),
+            coins("350basecro")
+        );
+        assert!(account.locked_coins(2000).is_empty());
+
+        let json = format!(
+            r#"{{"@type":"/cosmos.vesting.v1beta1.PeriodicVestingAccount","base_vesting_account":{{"base_account":{},"original_vesting":[{{"denom":"basecro","amount":"300"}}],"delegated_free":[],"delegated_vesting":[],"end_time":"1300"}},"start_time":"1000","vesting_periods":[{{"length":"100","amount":[{{"denom":"basecro","amount":"100"}}]}},{{"length":"200","amount":[{{"denom":"basecro","amount":"200"}}]}}]}}"#,
+            BASE
+        );
+        let account = Account::from_json(&serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(account.locked_coins(1099), coins("300basecro"));
+        assert_eq!(account.locked_coins(1100), coins("200basecro"));
+        assert!(account.locked_coins(1300).is_empty());
+
+        let json = format!(
+            r#"{{"@type":"/cosmos.vesting.v1beta1.DelayedVestingAccount","base_vesting_account":{{"base_account":{},"original_vesting":[{{"denom":"basecro","amount":"300"}}],"delegated_free":[],"delegated_vesting":[],"end_time":"1300"}}}}"#,
+            BASE
+        );
+        let account = Account::from_json(&serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(account.locked_coins(1299), coins("300basecro"));
+        assert!(account.locked_coins(1300).is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::constant::{
+    ACCOUNT_ADDRESS_PREFIX, CONSENSUS_ADDRESS_SUFFIX, TESTNET_ACCOUNT_ADDRESS_PREFIX,
+    VALIDATOR_ADDRESS_SUFFIX,
+};
+use crate::error::Error;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// bech32 prefixes of account addresses on the known networks
+const KNOWN_ACCOUNT_PREFIXES: &[&str] = &[ACCOUNT_ADDRESS_PREFIX, TESTNET_ACCOUNT_ADDRESS_PREFIX];
+
+/// role of an address, which determines its bech32 prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressKind {
+    /// account address, e.g. "cro1..."
+    Account,
+    /// validator operator address, e.g. "crocncl1..."
+    Validator,
+    /// validator consensus address, e.g. "crocnclcons1..."
+    Consensus,
+}
+
+impl AddressKind {
+    fn suffix(&self) -> &'static str {
+        match self {
+            AddressKind::Account => "",
+            AddressKind::Validator => VALIDATOR_ADDRESS_SUFFIX,
+            AddressKind::Consensus => CONSENSUS_ADDRESS_SUFFIX,
+        }
+    }
+}
+
+impl fmt::Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            AddressKind::Account => "account",
+            AddressKind::Validator => "validator",
+            AddressKind::Consensus => "consensus",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// bech32 address of an account, validator operator or validator consensus key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    /// account address prefix of the network, e.g. "cro"
+    prefix: String,
+    kind: AddressKind,
+    inner: stdtx::Address,
+}
+
+impl Address {
+    /// create an address from raw bytes, `prefix` is the account address prefix of the network
+    pub fn new(prefix: impl Into<String>, kind: AddressKind, inner: stdtx::Address) -> Self {
+        Self {
+            prefix: prefix.into(),
+            kind,
+            inner,
+        }
+    }
+
+    /// parse a bech32 address of the mainnet ("cro") or testnet ("tcro") networks
+    pub fn from_bech32(address: &str) -> Result<Self, Error> {
+        let (hrp, _) = decode(address)?;
+        let prefix = KNOWN_ACCOUNT_PREFIXES
+            .iter()
+            .find(|prefix| {
+                [
+                    AddressKind::Account,
+                    AddressKind::Validator,
+                    AddressKind::Consensus,
+                ]
+                .iter()
+                .any(|kind| hrp == format!("{}{}", prefix, kind.suffix()))
+            })
+            .ok_or_else(|| Error::InputError(format!("unknown address prefix: {}", hrp)))?;
+        Self::from_bech32_with_prefix(address, prefix)
+    }
+
+    /// parse a bech32 address of a network with the account address prefix `prefix`
+    pub fn from_bech32_with_prefix(address: &str, prefix: &str) -> Result<Self, Error> {
+        let (hrp, inner) = decode(address)?;
+        let kind = if hrp == prefix {
+            AddressKind::Account
+        } else if hrp == format!("{}{}", prefix, VALIDATOR_ADDRESS_SUFFIX) {
+            AddressKind::Validator
+        } else if hrp == format!("{}{}", prefix, CONSENSUS_ADDRESS_SUFFIX) {
+            AddressKind::Consensus
+        } else {
+            return Err(Error::InputError(format!(
+                "invalid address prefix {}, expected {}",
+                hrp, prefix
+            )));
+        };
+        Ok(Self::new(prefix, kind, inner))
+    }
+
+    /// role of the address
+    pub fn kind(&self) -> AddressKind {
+        self.kind
+    }
+
+    /// account address prefix of the network
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// bech32 human readable part, e.g. "crocncl" for a mainnet validator operator address
+    pub fn hrp(&self) -> String {
+        format!("{}{}", self.prefix, self.kind.suffix())
+    }
+
+    /// raw address bytes
+    pub fn raw(&self) -> stdtx::Address {
+        self.inner
+    }
+
+    /// fail unless the address has the expected role
+    pub fn ensure_kind(&self, kind: AddressKind) -> Result<&Self, Error> {
+        if self.kind != kind {
+            return Err(Error::InputError(format!(
+                "expected {} address, got {} address {}",
+                kind, self.kind, self
+            )));
+        }
+        Ok(self)
+    }
+
+    /// bech32 string of an account address, fails for validator or consensus addresses
+    pub fn account_bech32(&self) -> Result<String, Error> {
+        Ok(self.ensure_kind(AddressKind::Account)?.to_string())
+    }
+
+    /// bech32 string of a validator operator address, fails for other roles
+    pub fn validator_bech32(&self) -> Result<String, Error> {
+        Ok(self.ensure_kind(AddressKind::Validator)?.to_string())
+    }
+
+    /// the same bytes as an address of another role
+    pub fn to_kind(&self, kind: AddressKind) -> Self {
+        Self::new(self.prefix.clone(), kind, self.inner)
+    }
+
+    /// the same bytes as an account address
+    pub fn to_account(&self) -> Self {
+        self.to_kind(AddressKind::Account)
+    }
+
+    /// the same bytes as a validator operator address
+    pub fn to_validator(&self) -> Self {
+        self.to_kind(AddressKind::Validator)
+    }
+
+    /// the same bytes as a validator consensus address
+    pub fn to_consensus(&self) -> Self {
+        self.to_kind(AddressKind::Consensus)
+    }
+}
+
+fn decode(address: &str) -> Result<(String, stdtx::Address), Error> {
+    stdtx::Address::from_bech32(address)
+        .map_err(|e| Error::InputError(format!("invalid bech32 address {}: {}", address, e)))
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner.to_bech32(&self.hrp()))
+    }
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bech32(s)
+    }
+}
+
+impl From<&Address> for stdtx::Address {
+    fn from(address: &Address) -> Self {
+        address.inner
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        Self::from_bech32(&address).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_address() {
+        let address: Address = "cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppf"
+            .parse()
+            .unwrap();
+        assert_eq!(address.kind(), AddressKind::Account);
+        assert_eq!(address.prefix(), "cro");
+        assert!(address.ensure_kind(AddressKind::Account).is_ok());
+        assert!(address.ensure_kind(AddressKind::Validator).is_err());
+
+        let validator = address.to_validator();
+        assert_eq!(validator.hrp(), "crocncl");
+        let parsed: Address = validator.to_string().parse().unwrap();
+        assert_eq!(parsed, validator);
+        assert_eq!(parsed.kind(), AddressKind::Validator);
+
+        let consensus = address.to_consensus();
+        assert_eq!(consensus.hrp(), "crocnclcons");
+        let parsed: Address = consensus.to_string().parse().unwrap();
+        assert_eq!(parsed.kind(), AddressKind::Consensus);
+        assert_eq!(parsed.to_account(), address);
+
+        let testnet = Address::new("tcro", AddressKind::Account, address.raw());
+        let parsed: Address = testnet.to_string().parse().unwrap();
+        assert_eq!(parsed.prefix(), "tcro");
+
+        assert!("cosmos1u9q8mfpzhyv2s43js7l5qseapx5kt3g2nwvsv0"
+            .parse::<Address>()
+            .is_err());
+        assert!("cro1u9q8mfpzhyv2s43js7l5qseapx5kt3g2rf7ppx"
+            .parse::<Address>()
+            .is_err());
+        assert!(Address::from_bech32_with_prefix(&address.to_string(), "tcro").is_err());
+    }
+}
@@ -1,9 +1,15 @@
 use crate::constant::CRO;
-use crate::utils::codec::serde_to_str;
-use serde::Serialize;
+use crate::error::Error;
+use crate::network::Network;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::tx::v1beta1::BroadcastMode;
+use crate::types::coin::Coin;
+use crate::utils::codec::{serde_from_str, serde_to_str};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 
 /// sync mode when send the transaction
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum SyncMode {
     /// synchronous
@@ -14,8 +20,30 @@ pub enum SyncMode {
     Block,
 }
 
+impl SyncMode {
+    /// name of the mode in the Cosmos SDK tx service, e.g. "BROADCAST_MODE_SYNC"
+    pub fn broadcast_mode_name(&self) -> &'static str {
+        match self {
+            SyncMode::Sync => "BROADCAST_MODE_SYNC",
+            SyncMode::Async => "BROADCAST_MODE_ASYNC",
+            SyncMode::Block => "BROADCAST_MODE_BLOCK",
+        }
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<SyncMode> for BroadcastMode {
+    fn from(mode: SyncMode) -> Self {
+        match mode {
+            SyncMode::Sync => BroadcastMode::Sync,
+            SyncMode::Async => BroadcastMode::Async,
+            SyncMode::Block => BroadcastMode::Block,
+        }
+    }
+}
+
 /// denomination: 1Cro = 100_000_000 Basecro
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum Denom {
     /// base unit
@@ -24,33 +52,132 @@ pub enum Denom {
     Cro,
 }
 
-/// Amount
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// Amount in the base denomination of a network, e.g. "basecro" or "basetcro"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "JsonAmount")]
 pub struct Amount {
-    denom: Denom,
+    denom: String,
     #[serde(serialize_with = "serde_to_str")]
     amount: u64,
 }
 
+/// amount as written in JSON, the denomination is kept as is
+#[derive(Deserialize)]
+struct JsonAmount {
+    denom: String,
+    #[serde(deserialize_with = "serde_from_str")]
+    amount: u64,
+}
+
+impl TryFrom<JsonAmount> for Amount {
+    type Error = Error;
+
+    fn try_from(amount: JsonAmount) -> Result<Self, Self::Error> {
+        let coin = Coin::new(amount.amount as u128, amount.denom)?;
+        Ok(Self {
+            denom: coin.denom().to_string(),
+            amount: amount.amount,
+        })
+    }
+}
+
 impl Amount {
     /// create a new amount, whatever input will be a Benom::Basecro denomination result
+    ///
+    /// panics if the amount in basecro overflows
+    #[deprecated(note = "panics on overflow, use `Amount::try_new`")]
     pub fn new(amount: u64, denom: Denom) -> Self {
+        Self::try_new(amount, denom).expect("amount overflow")
+    }
+
+    /// create a new amount in Denom::Basecro denomination, fails if it overflows
+    pub fn try_new(amount: u64, denom: Denom) -> Result<Self, Error> {
+        Self::for_network(amount, denom, &Network::mainnet())
+    }
+
+    /// create a new amount in the base denomination of `network`, `Denom::Basecro` stands for
+    /// its base unit and `Denom::Cro` for its display unit (e.g. "tcro" on the testnet)
+    pub fn for_network(amount: u64, denom: Denom, network: &Network) -> Result<Self, Error> {
         let amount = match denom {
-            Denom::Basecro => amount,
-            Denom::Cro => amount * CRO,
-        };
+            Denom::Basecro => Some(amount),
+            Denom::Cro => amount.checked_mul(CRO),
+        }
+        .ok_or_else(|| Error::InputError("amount overflow".to_string()))?;
+        Ok(Self {
+            denom: network.base_denom.clone(),
+            amount,
+        })
+    }
+
+    /// amount of an already validated denomination
+    pub(crate) fn from_parts(denom: String, amount: u64) -> Self {
+        Self { denom, amount }
+    }
+
+    /// the same amount of base units in the base denomination of `network`
+    pub fn on_network(&self, network: &Network) -> Self {
         Self {
-            denom: Denom::Basecro,
+            denom: network.base_denom.clone(),
+            amount: self.amount,
+        }
+    }
+
+    /// the amount in the base denomination if it is in the display denomination of a built-in
+    /// network, e.g. 2cro is 200000000basecro, other amounts are returned unchanged
+    pub fn to_base_denom(&self) -> Result<Self, Error> {
+        for network in [Network::mainnet(), Network::testnet()] {
+            if self.denom == network.display_denom {
+                return Amount::for_network(self.amount, Denom::Cro, &network);
+            }
+        }
+        Ok(self.clone())
+    }
+
+    /// amount in the base unit
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// base denomination of the amount, e.g. "basecro"
+    pub fn denom(&self) -> &str {
+        &self.denom
+    }
+
+    /// add two amounts of the same denomination, `None` on overflow or denomination mismatch
+    pub fn checked_add(&self, other: &Amount) -> Option<Amount> {
+        if self.denom != other.denom {
+            return None;
+        }
+        let amount = self.amount.checked_add(other.amount)?;
+        Some(Self {
+            denom: self.denom.clone(),
             amount,
+        })
+    }
+
+    /// sum up amounts per denomination, the result is sorted by denomination
+    pub fn sum(amounts: &[Amount]) -> Result<Vec<Amount>, Error> {
+        let mut total: Vec<Amount> = vec![];
+        for amount in amounts {
+            match total.iter_mut().find(|t| t.denom == amount.denom) {
+                Some(t) => {
+                    *t = t
+                        .checked_add(amount)
+                        .ok_or_else(|| Error::InputError("amount overflow".to_string()))?;
+                }
+                None => total.push(amount.clone()),
+            }
         }
+        total.sort_by(|a, b| a.denom.cmp(&b.denom));
+        Ok(total)
     }
 }
 
 /// transaction fee
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Fee {
     /// gas limit
-    #[serde(serialize_with = "serde_to_str")]
+    #[serde(serialize_with = "serde_to_str", deserialize_with = "serde_from_str")]
     pub gas: u64,
     /// fee to be paid
     pub amount: Vec<Amount>,
@@ -64,3 +191,35 @@ impl Default for Fee {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_amount() {
+        for json in [
+            r#"{"denom":"cro","amount":"2"}"#,
+            r#"{"denom":"basecro","amount":"200000000"}"#,
+            r#"{"denom":"ibc/6B5A664BF0AF4F71B2F0BAA33141E2F1321242FBD5D19762F541EC971ACB0865","amount":"5"}"#,
+        ] {
+            let amount: Amount = serde_json::from_str(json).unwrap();
+            assert_eq!(serde_json::to_string(&amount).unwrap(), json);
+        }
+        let amount: Amount = serde_json::from_str(r#"{"denom":"cro","amount":"2"}"#).unwrap();
+        assert_eq!(amount.denom(), "cro");
+        assert_eq!(amount.amount(), 2);
+        assert_eq!(
+            amount.to_base_denom().unwrap(),
+            Amount::try_new(200000000, Denom::Basecro).unwrap()
+        );
+        let testnet: Amount = serde_json::from_str(r#"{"denom":"tcro","amount":"2"}"#).unwrap();
+        let testnet = testnet.to_base_denom().unwrap();
+        assert_eq!(testnet.denom(), "basetcro");
+        assert_eq!(testnet.amount(), 200000000);
+        let overflow: Amount =
+            serde_json::from_str(&format!(r#"{{"denom":"cro","amount":"{}"}}"#, u64::MAX)).unwrap();
+        assert!(overflow.to_base_denom().is_err());
+        assert!(serde_json::from_str::<Amount>(r#"{"denom":"1x","amount":"2"}"#).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+use crate::error::Error;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::base::abci::v1beta1::{StringEvent, TxResponse};
+#[cfg(feature = "grpc")]
+use crate::proto::tendermint::abci::Event as AbciEvent;
+#[cfg(feature = "grpc")]
+use crate::proto::tendermint::rpc::grpc::ResponseBroadcastTx;
+use serde::{Deserialize, Serialize};
+
+/// codespace of errors raised by the Cosmos SDK itself
+pub const SDK_CODESPACE: &str = "sdk";
+
+/// attribute of a transaction event
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EventAttribute {
+    /// attribute name, e.g. "recipient"
+    pub key: String,
+    /// attribute value
+    #[serde(default)]
+    pub value: String,
+}
+
+/// event emitted while executing a transaction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// event type, e.g. "transfer"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// event attributes in emitted order
+    #[serde(default)]
+    pub attributes: Vec<EventAttribute>,
+}
+
+impl Event {
+    /// value of the first attribute named `key`
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.as_str())
+    }
+}
+
+/// common Cosmos SDK errors of rejected transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SdkError {
+    /// the transaction could not be decoded
+    TxDecode,
+    /// signature verification failed
+    Unauthorized,
+    /// the sender cannot pay the amount or fee
+    InsufficientFunds,
+    /// the gas limit was exceeded
+    OutOfGas,
+    /// the fee is below the minimum gas price of the node
+    InsufficientFee,
+    /// the transaction is already in the mempool
+    TxInMempoolCache,
+    /// the mempool of the node is full
+    MempoolIsFull,
+    /// the transaction was signed for another chain id
+    InvalidChainId,
+    /// the account sequence signed is not the expected one
+    WrongSequence {
+        /// sequence expected by the chain, if given in the log
+        expected: Option<u64>,
+        /// sequence of the transaction, if given in the log
+        got: Option<u64>,
+    },
+    /// any other error
+    Other {
+        /// module the code belongs to
+        codespace: String,
+        /// error code
+        code: u32,
+    },
+}
+
+impl SdkError {
+    /// map a failed transaction code, `None` if the code means success
+    pub fn from_code(codespace: &str, code: u32, raw_log: &str) -> Option<Self> {
+        if code == 0 {
+            return None;
+        }
+        let error = match (codespace, code) {
+            (SDK_CODESPACE, 2) => SdkError::TxDecode,
+            (SDK_CODESPACE, 4) => SdkError::Unauthorized,
+            (SDK_CODESPACE, 5) => SdkError::InsufficientFunds,
+            (SDK_CODESPACE, 11) => SdkError::OutOfGas,
+            (SDK_CODESPACE, 13) => SdkError::InsufficientFee,
+            (SDK_CODESPACE, 19) => SdkError::TxInMempoolCache,
+            (SDK_CODESPACE, 20) => SdkError::MempoolIsFull,
+            (SDK_CODESPACE, 28) => SdkError::InvalidChainId,
+            (SDK_CODESPACE, 32) => SdkError::WrongSequence {
+                expected: number_after(raw_log, "expected "),
+                got: number_after(raw_log, "got "),
+            },
+            _ => SdkError::Other {
+                codespace: codespace.to_string(),
+                code,
+            },
+        };
+        Some(error)
+    }
+
+    /// whether broadcasting again can succeed, possibly after re-signing with the expected sequence
+    pub fn is_retriable(&self) -> bool {
+        matches!(
+            self,
+            SdkError::WrongSequence { .. } | SdkError::MempoolIsFull | SdkError::TxInMempoolCache
+        )
+    }
+}
+
+/// number following `prefix` in a log, e.g. 25 in "expected 25, got 24"
+fn number_after(log: &str, prefix: &str) -> Option<u64> {
+    let start = log.find(prefix)? + prefix.len();
+    let digits: String = log[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// outcome of a broadcasted transaction
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[must_use]
+pub struct BroadcastResult {
+    /// transaction hash in upper case hex
+    pub hash: String,
+    /// block height, 0 if the transaction is not committed yet
+    pub height: u64,
+    /// 0 on success
+    pub code: u32,
+    /// module the code belongs to
+    pub codespace: String,
+    /// execution log, the error message for failed transactions
+    pub raw_log: String,
+    /// gas limit of the transaction
+    pub gas_wanted: u64,
+    /// gas used executing the transaction
+    pub gas_used: u64,
+    /// events emitted executing the transaction
+    pub events: Vec<Event>,
+    /// block time in RFC 3339, empty if the transaction is not committed yet
+    pub timestamp: String,
+}
+
+impl BroadcastResult {
+    /// whether the transaction was accepted
+    pub fn is_ok(&self) -> bool {
+        self.code == 0
+    }
+
+    /// mapped error of a rejected transaction
+    pub fn error(&self) -> Option<SdkError> {
+        SdkError::from_code(&self.codespace, self.code, &self.raw_log)
+    }
+
+    /// the result if the transaction was accepted, otherwise `Error::NodeError`
+    pub fn into_result(self) -> Result<Self, Error> {
+        if self.is_ok() {
+            Ok(self)
+        } else {
+            Err(Error::NodeError {
+                code: self.code,
+                codespace: self.codespace,
+                raw_log: self.raw_log,
+            })
+        }
+    }
+
+    /// events of type `kind`
+    pub fn events_of<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a Event> + 'a {
+        self.events.iter().filter(move |event| event.kind == kind)
+    }
+
+    /// parse a `TxResponse` JSON of the REST API
+    pub fn from_json(response: &serde_json::Value) -> Result<Self, Error> {
+        // {'height': '0', 'txhash': '...', 'codespace': '', 'code': 0, 'raw_log': '[...]', 'logs': [{'msg_index': 0, 'log': '', 'events': [...]}], 'gas_wanted': '200000', 'gas_used': '62545'}
+        let hash = response["txhash"]
+            .as_str()
+            .ok_or_else(|| Error::ClientError(format!("no tx hash in response: {}", response)))?;
+        let number = |key: &str| -> u64 {
+            match &response[key] {
+                serde_json::Value::String(value) => value.parse().unwrap_or_default(),
+                value => value.as_u64().unwrap_or_default(),
+            }
+        };
+        let mut events = vec![];
+        for log in response["logs"].as_array().into_iter().flatten() {
+            if let Some(log_events) = log.get("events") {
+                let log_events: Vec<Event> =
+                    serde_json::from_value(log_events.clone()).map_err(|e| {
+                        Error::ClientError(format!("invalid events in response: {}", e))
+                    })?;
+                events.extend(log_events);
+            }
+        }
+        Ok(Self {
+            hash: hash.to_string(),
+            height: number("height"),
+            code: number("code") as u32,
+            codespace: response["codespace"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            raw_log: response["raw_log"].as_str().unwrap_or_default().to_string(),
+            gas_wanted: number("gas_wanted"),
+            gas_used: number("gas_used"),
+            events,
+            timestamp: response["timestamp"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+
+    /// result of the Tendermint gRPC broadcast API, which does not return the hash and height
+    #[cfg(feature = "grpc")]
+    pub fn from_response(hash: String, response: &ResponseBroadcastTx) -> Self {
+        let mut result = Self {
+            hash,
+            ..Default::default()
+        };
+        if let Some(check_tx) = &response.check_tx {
+            result.code = check_tx.code;
+            result.codespace = check_tx.codespace.clone();
+            result.raw_log = check_tx.log.clone();
+            result.gas_wanted = check_tx.gas_wanted.max(0) as u64;
+            result.gas_used = check_tx.gas_used.max(0) as u64;
+            result
+                .events
+                .extend(check_tx.events.iter().map(Event::from));
+        }
+        match &response.deliver_tx {
+            Some(deliver_tx) if result.is_ok() => {
+                result.code = deliver_tx.code;
+                result.codespace = deliver_tx.codespace.clone();
+                result.raw_log = deliver_tx.log.clone();
+                result.gas_wanted = deliver_tx.gas_wanted.max(0) as u64;
+                result.gas_used = deliver_tx.gas_used.max(0) as u64;
+                result
+                    .events
+                    .extend(deliver_tx.events.iter().map(Event::from));
+            }
+            _ => {}
+        }
+        result
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<&TxResponse> for BroadcastResult {
+    /// result of the Cosmos SDK tx service, events are only set for committed transactions
+    fn from(response: &TxResponse) -> Self {
+        Self {
+            hash: response.txhash.clone(),
+            height: response.height.max(0) as u64,
+            code: response.code,
+            codespace: response.codespace.clone(),
+            raw_log: response.raw_log.clone(),
+            gas_wanted: response.gas_wanted.max(0) as u64,
+            gas_used: response.gas_used.max(0) as u64,
+            events: response
+                .logs
+                .iter()
+                .flat_map(|log| log.events.iter().map(Event::from))
+                .collect(),
+            timestamp: response.timestamp.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<&StringEvent> for Event {
+    fn from(event: &StringEvent) -> Self {
+        Self {
+            kind: event.r#type.clone(),
+            attributes: event
+                .attributes
+                .iter()
+                .map(|attribute| EventAttribute {
+                    key: attribute.key.clone(),
+                    value: attribute.value.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<&AbciEvent> for Event {
+    fn from(event: &AbciEvent) -> Self {
+        Self {
+            kind: event.r#type.clone(),
+            attributes: event
+                .attributes
+                .iter()
+                .map(|attribute| EventAttribute {
+                    key: String::from_utf8_lossy(&attribute.key).into_owned(),
+                    value: String::from_utf8_lossy(&attribute.value).into_owned(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_broadcast_result_from_json() {
+        let response = serde_json::json!({
+            "height": "1024",
+            "txhash": "BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D",
+            "codespace": "",
+            "code": 0,
+            "raw_log": "[]",
+            "logs": [{
+                "msg_index": 0,
+                "log": "",
+                "events": [
+                    {"type": "message", "attributes": [{"key": "action", "value": "send"}]},
+                    {"type": "transfer", "attributes": [
+                        {"key": "recipient", "value": "cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9"},
+                        {"key": "amount", "value": "100000000basecro"}
+                    ]}
+                ]
+            }],
+            "gas_wanted": "200000",
+            "gas_used": "62545",
+            "timestamp": "2022-01-10T08:01:23Z"
+        });
+        let result = BroadcastResult::from_json(&response).unwrap();
+        assert!(result.is_ok());
+        assert_eq!(result.height, 1024);
+        assert_eq!(result.timestamp, "2022-01-10T08:01:23Z");
+        assert_eq!(result.gas_wanted, 200000);
+        assert_eq!(result.gas_used, 62545);
+        assert_eq!(result.error(), None);
+        let transfer = result.events_of("transfer").next().unwrap();
+        assert_eq!(transfer.attribute("amount"), Some("100000000basecro"));
+        assert!(result.into_result().is_ok());
+    }
+
+    #[test]
+    fn test_sdk_error() {
+        let response = serde_json::json!({
+            "height": "0",
+            "txhash": "BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D",
+            "codespace": "sdk",
+            "code": 32,
+            "raw_log": "account sequence mismatch, expected 25, got 24: incorrect account sequence"
+        });
+        let result = BroadcastResult::from_json(&response).unwrap();
+        let error = result.error().unwrap();
+        assert_eq!(
+            error,
+            SdkError::WrongSequence {
+                expected: Some(25),
+                got: Some(24)
+            }
+        );
+        assert!(error.is_retriable());
+        let error = result.into_result().unwrap_err();
+        assert!(matches!(
+            error.sdk_error(),
+            Some(SdkError::WrongSequence { .. })
+        ));
+
+        assert_eq!(
+            SdkError::from_code("sdk", 5, ""),
+            Some(SdkError::InsufficientFunds)
+        );
+        assert_eq!(SdkError::from_code("sdk", 11, ""), Some(SdkError::OutOfGas));
+        assert!(!SdkError::OutOfGas.is_retriable());
+        assert_eq!(
+            SdkError::from_code("nft", 5, ""),
+            Some(SdkError::Other {
+                codespace: "nft".to_string(),
+                code: 5
+            })
+        );
+        assert_eq!(SdkError::from_code("", 0, ""), None);
+    }
+}
@@ -0,0 +1,459 @@
+use crate::error::Error;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+use crate::types::basic::Amount;
+use crate::types::denom::{DenomMetadata, BUILTIN_DENOMS};
+use crate::utils::codec::{serde_from_str, serde_to_str};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// check a denomination against the cosmos-sdk rule `[a-zA-Z][a-zA-Z0-9/:._-]{2,127}`
+fn validate_denom(denom: &str) -> Result<(), Error> {
+    let valid = denom.len() >= 3
+        && denom.len() <= 128
+        && denom.starts_with(|c: char| c.is_ascii_alphabetic())
+        && denom
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InputError(format!("invalid denom: {}", denom)))
+    }
+}
+
+/// coin of an arbitrary denomination (e.g. "basecro" or "ibc/<hash>") in its base unit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "JsonCoin")]
+pub struct Coin {
+    denom: String,
+    #[serde(serialize_with = "serde_to_str")]
+    amount: u128,
+}
+
+/// coin as written in JSON, validated by `Coin::new`
+#[derive(Deserialize)]
+struct JsonCoin {
+    denom: String,
+    #[serde(deserialize_with = "serde_from_str")]
+    amount: u128,
+}
+
+impl TryFrom<JsonCoin> for Coin {
+    type Error = Error;
+
+    fn try_from(coin: JsonCoin) -> Result<Self, Self::Error> {
+        Coin::new(coin.amount, coin.denom)
+    }
+}
+
+impl Coin {
+    /// create a new coin, `amount` is in the base unit of `denom`
+    pub fn new(amount: u128, denom: impl Into<String>) -> Result<Self, Error> {
+        let denom = denom.into();
+        validate_denom(&denom)?;
+        Ok(Self { denom, amount })
+    }
+
+    /// amount in the base unit
+    pub fn amount(&self) -> u128 {
+        self.amount
+    }
+
+    /// base denomination
+    pub fn denom(&self) -> &str {
+        &self.denom
+    }
+
+    /// whether the amount is zero
+    pub fn is_zero(&self) -> bool {
+        self.amount == 0
+    }
+
+    /// add a coin of the same denomination, `None` on overflow or denomination mismatch
+    pub fn checked_add(&self, other: &Coin) -> Option<Coin> {
+        if self.denom != other.denom {
+            return None;
+        }
+        let amount = self.amount.checked_add(other.amount)?;
+        Some(Self {
+            denom: self.denom.clone(),
+            amount,
+        })
+    }
+
+    /// subtract a coin of the same denomination, `None` on underflow or denomination mismatch
+    pub fn checked_sub(&self, other: &Coin) -> Option<Coin> {
+        if self.denom != other.denom {
+            return None;
+        }
+        let amount = self.amount.checked_sub(other.amount)?;
+        Some(Self {
+            denom: self.denom.clone(),
+            amount,
+        })
+    }
+
+    /// amount in the display unit if the denomination is known, e.g. "1.5cro" for 150000000basecro
+    pub fn to_display_string(&self) -> String {
+        match BUILTIN_DENOMS.iter().find(|(base, ..)| *base == self.denom) {
+            Some((base, display, symbol, exponent)) => {
+                let metadata = DenomMetadata::new(*base, *display, *symbol, *exponent);
+                format!("{}{}", metadata.format_amount(self.amount), display)
+            }
+            None => self.to_string(),
+        }
+    }
+}
+
+impl PartialOrd for Coin {
+    /// coins of different denominations are not comparable
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.denom != other.denom {
+            return None;
+        }
+        Some(self.amount.cmp(&other.amount))
+    }
+}
+
+impl fmt::Display for Coin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
+
+impl FromStr for Coin {
+    type Err = Error;
+
+    /// parse "100basecro", "1.5cro" or "5ibc/<hash>", display units are converted to base units
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(|| Error::InputError(format!("missing denom in coin: {}", s)))?;
+        let (amount, denom) = s.split_at(split);
+        let (whole, fraction) = match amount.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (amount, ""),
+        };
+        if whole.is_empty()
+            || fraction.contains('.')
+            || (amount.contains('.') && fraction.is_empty())
+        {
+            return Err(Error::InputError(format!("invalid coin amount: {}", s)));
+        }
+        let (denom, exponent) = match BUILTIN_DENOMS
+            .iter()
+            .find(|(_, display, ..)| *display == denom)
+        {
+            Some((base, _, _, exponent)) => (*base, *exponent),
+            None => (denom, 0),
+        };
+        if fraction.len() > exponent as usize {
+            return Err(Error::InputError(format!(
+                "too many decimal places for {}: {}",
+                denom, s
+            )));
+        }
+        let overflow = || Error::InputError(format!("coin amount overflow: {}", s));
+        let whole: u128 = whole
+            .parse()
+            .map_err(|_| Error::InputError(format!("invalid coin amount: {}", s)))?;
+        let fraction_padded = format!("{:0<width$}", fraction, width = exponent as usize);
+        let fraction: u128 = if fraction_padded.is_empty() {
+            0
+        } else {
+            fraction_padded
+                .parse()
+                .map_err(|_| Error::InputError(format!("invalid coin amount: {}", s)))?
+        };
+        let amount = whole
+            .checked_mul(10u128.pow(exponent))
+            .and_then(|a| a.checked_add(fraction))
+            .ok_or_else(overflow)?;
+        Coin::new(amount, denom)
+    }
+}
+
+impl From<Amount> for Coin {
+    fn from(amount: Amount) -> Self {
+        Self {
+            denom: amount.denom().to_string(),
+            amount: amount.amount() as u128,
+        }
+    }
+}
+
+impl TryFrom<Coin> for Amount {
+    type Error = Error;
+
+    fn try_from(coin: Coin) -> Result<Self, Self::Error> {
+        let amount = u64::try_from(coin.amount)
+            .map_err(|_| Error::InputError(format!("amount overflow: {}", coin)))?;
+        Ok(Amount::from_parts(coin.denom, amount))
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<Coin> for ProtoCoin {
+    fn from(coin: Coin) -> Self {
+        ProtoCoin {
+            denom: coin.denom,
+            amount: coin.amount.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl TryFrom<ProtoCoin> for Coin {
+    type Error = Error;
+
+    fn try_from(coin: ProtoCoin) -> Result<Self, Self::Error> {
+        let amount = coin
+            .amount
+            .parse()
+            .map_err(|_| Error::InputError(format!("invalid coin amount: {}", coin.amount)))?;
+        Coin::new(amount, coin.denom)
+    }
+}
+
+/// set of coins with distinct denominations, sorted by denomination and without zero amounts
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[serde(try_from = "Vec<Coin>", into = "Vec<Coin>")]
+pub struct Coins(Vec<Coin>);
+
+impl Coins {
+    /// create a new set of coins, amounts of the same denomination are summed up
+    pub fn new(coins: Vec<Coin>) -> Result<Self, Error> {
+        let mut result = Self::default();
+        for coin in coins {
+            result.add_coin(&coin)?;
+        }
+        Ok(result)
+    }
+
+    fn add_coin(&mut self, coin: &Coin) -> Result<(), Error> {
+        if coin.is_zero() {
+            return Ok(());
+        }
+        match self.0.binary_search_by(|c| c.denom.cmp(&coin.denom)) {
+            Ok(i) => {
+                self.0[i] = self.0[i]
+                    .checked_add(coin)
+                    .ok_or_else(|| Error::InputError(format!("coin overflow: {}", coin.denom)))?;
+            }
+            Err(i) => self.0.insert(i, coin.clone()),
+        }
+        Ok(())
+    }
+
+    /// amount of a denomination, zero if absent
+    pub fn amount_of(&self, denom: &str) -> u128 {
+        self.0
+            .iter()
+            .find(|c| c.denom == denom)
+            .map(|c| c.amount)
+            .unwrap_or_default()
+    }
+
+    /// whether there are no coins
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// iterate coins sorted by denomination
+    pub fn iter(&self) -> impl Iterator<Item = &Coin> {
+        self.0.iter()
+    }
+
+    /// sum of two sets of coins, `None` on overflow
+    pub fn checked_add(&self, other: &Coins) -> Option<Coins> {
+        let mut result = self.clone();
+        for coin in other.iter() {
+            result.add_coin(coin).ok()?;
+        }
+        Some(result)
+    }
+
+    /// difference of two sets of coins, `None` if any amount would become negative
+    pub fn checked_sub(&self, other: &Coins) -> Option<Coins> {
+        let mut result = self.clone();
+        for coin in other.iter() {
+            let i = result
+                .0
+                .binary_search_by(|c| c.denom.cmp(&coin.denom))
+                .ok()?;
+            let rest = result.0[i].checked_sub(coin)?;
+            if rest.is_zero() {
+                result.0.remove(i);
+            } else {
+                result.0[i] = rest;
+            }
+        }
+        Some(result)
+    }
+
+    /// difference of two sets of coins, amounts that would become negative are dropped
+    pub fn saturating_sub(&self, other: &Coins) -> Coins {
+        let coins = self
+            .0
+            .iter()
+            .filter_map(|coin| {
+                let amount = coin.amount.saturating_sub(other.amount_of(&coin.denom));
+                (amount > 0).then(|| Coin {
+                    denom: coin.denom.clone(),
+                    amount,
+                })
+            })
+            .collect();
+        Coins(coins)
+    }
+
+    /// whether every amount of `other` is covered by this set of coins
+    pub fn is_all_gte(&self, other: &Coins) -> bool {
+        other.iter().all(|c| self.amount_of(&c.denom) >= c.amount)
+    }
+}
+
+impl TryFrom<Vec<Coin>> for Coins {
+    type Error = Error;
+
+    fn try_from(coins: Vec<Coin>) -> Result<Self, Self::Error> {
+        Coins::new(coins)
+    }
+}
+
+impl From<Coins> for Vec<Coin> {
+    fn from(coins: Coins) -> Self {
+        coins.0
+    }
+}
+
+impl From<Coin> for Coins {
+    fn from(coin: Coin) -> Self {
+        if coin.is_zero() {
+            Coins::default()
+        } else {
+            Coins(vec![coin])
+        }
+    }
+}
+
+impl fmt::Display for Coins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let coins: Vec<String> = self.0.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", coins.join(","))
+    }
+}
+
+impl FromStr for Coins {
+    type Err = Error;
+
+    /// parse comma separated coins, e.g. "1.5cro,100ibc/<hash>"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Coins::default());
+        }
+        let coins = s
+            .split(',')
+            .map(Coin::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Coins::new(coins)
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<Coins> for Vec<ProtoCoin> {
+    fn from(coins: Coins) -> Self {
+        coins.0.into_iter().map(ProtoCoin::from).collect()
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl TryFrom<Vec<ProtoCoin>> for Coins {
+    type Error = Error;
+
+    fn try_from(coins: Vec<ProtoCoin>) -> Result<Self, Self::Error> {
+        let coins = coins
+            .into_iter()
+            .map(Coin::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Coins::new(coins)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_coin() {
+        let coin: Coin = "1.5cro".parse().unwrap();
+        assert_eq!(coin, Coin::new(150_000_000, "basecro").unwrap());
+        assert_eq!(coin.to_string(), "150000000basecro");
+        assert_eq!(coin.to_display_string(), "1.5cro");
+
+        let coin: Coin = "100basecro".parse().unwrap();
+        assert_eq!(coin.amount(), 100);
+        assert_eq!(coin.to_display_string(), "0.000001cro");
+
+        let ibc = "ibc/14F9BC3E44B8A9C1BE1FB08980FAB87034C9905EF17CF2F5008FC085218811CC";
+        let coin: Coin = format!("5{}", ibc).parse().unwrap();
+        assert_eq!(coin.denom(), ibc);
+        assert_eq!(coin.to_display_string(), format!("5{}", ibc));
+
+        assert!("1.000000001cro".parse::<Coin>().is_err());
+        assert!("1.5basecro".parse::<Coin>().is_err());
+        assert!("cro".parse::<Coin>().is_err());
+        assert!("100".parse::<Coin>().is_err());
+        assert!("1.cro".parse::<Coin>().is_err());
+        assert!("1x".parse::<Coin>().is_err());
+    }
+
+    #[test]
+    fn test_deserialize_coins() {
+        let coins: Coins = serde_json::from_str(
+            r#"[{"denom":"cro","amount":"1"},{"denom":"basecro","amount":"2"},{"denom":"cro","amount":"3"},{"denom":"abc","amount":"0"}]"#,
+        )
+        .unwrap();
+        assert_eq!(coins.to_string(), "2basecro,4cro");
+        assert!(serde_json::from_str::<Coin>(r#"{"denom":"1x","amount":"1"}"#).is_err());
+        assert!(serde_json::from_str::<Coins>(r#"[{"denom":"b c","amount":"1"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_coins_arithmetic() {
+        let a: Coins = "1cro,5ibc/ABC".parse().unwrap();
+        let b: Coins = "0.5cro,5ibc/ABC".parse().unwrap();
+        assert_eq!(a.to_string(), "100000000basecro,5ibc/ABC");
+
+        let sum = a.checked_add(&b).unwrap();
+        assert_eq!(sum.amount_of("basecro"), 150_000_000);
+        assert_eq!(sum.amount_of("ibc/ABC"), 10);
+
+        let diff = a.checked_sub(&b).unwrap();
+        assert_eq!(diff.to_string(), "50000000basecro");
+        assert!(b.checked_sub(&a).is_none());
+        assert!(a.is_all_gte(&b));
+        assert!(!b.is_all_gte(&a));
+
+        let cro = Coin::new(1, "basecro").unwrap();
+        let ibc = Coin::new(1, "ibc/ABC").unwrap();
+        assert_eq!(cro.partial_cmp(&ibc), None);
+        assert!(cro < Coin::new(2, "basecro").unwrap());
+        assert!(Coin::new(u128::MAX, "basecro")
+            .unwrap()
+            .checked_add(&cro)
+            .is_none());
+
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"denom":"basecro","amount":"100000000"},{"denom":"ibc/ABC","amount":"5"}]"#
+        );
+        assert_eq!(serde_json::from_str::<Coins>(&json).unwrap(), a);
+    }
+}
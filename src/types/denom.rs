@@ -0,0 +1,312 @@
+use crate::error::Error;
+#[cfg(feature = "grpc")]
+use crate::proto::cosmos::bank::v1beta1::Metadata as BankMetadata;
+use crate::types::coin::Coin;
+use bitcoin_hashes::{sha256, Hash};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// (base denom, display denom, symbol, exponent) of well-known denominations
+pub(crate) const BUILTIN_DENOMS: &[(&str, &str, &str, u32)] = &[
+    ("basecro", "cro", "CRO", 8),
+    ("basetcro", "tcro", "TCRO", 8),
+    ("uatom", "atom", "ATOM", 6),
+    ("uosmo", "osmo", "OSMO", 6),
+];
+
+/// prefix of denominations received over IBC
+pub const IBC_DENOM_PREFIX: &str = "ibc/";
+
+/// whether `denom` is an "ibc/<hash>" denomination, the prefix is not case sensitive
+pub(crate) fn is_ibc_denom(denom: &str) -> bool {
+    matches!(
+        denom.get(..IBC_DENOM_PREFIX.len()),
+        Some(prefix) if prefix.eq_ignore_ascii_case(IBC_DENOM_PREFIX)
+    )
+}
+
+/// "ibc/<hash>" with the hash in upper case like `DenomTrace::ibc_denom`, other denominations as is
+fn canonical_ibc_denom(denom: &str) -> String {
+    if !is_ibc_denom(denom) {
+        return denom.to_string();
+    }
+    let hash = &denom[IBC_DENOM_PREFIX.len()..];
+    format!("{}{}", IBC_DENOM_PREFIX, hash.to_uppercase())
+}
+
+/// display metadata of a base denomination
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DenomMetadata {
+    /// base unit, e.g. "basecro"
+    pub base: String,
+    /// display unit, e.g. "cro"
+    pub display: String,
+    /// ticker symbol, e.g. "CRO"
+    pub symbol: String,
+    /// 1 display unit = 10^exponent base units
+    pub exponent: u32,
+}
+
+impl DenomMetadata {
+    /// create new denomination metadata
+    pub fn new(
+        base: impl Into<String>,
+        display: impl Into<String>,
+        symbol: impl Into<String>,
+        exponent: u32,
+    ) -> Self {
+        Self {
+            base: base.into(),
+            display: display.into(),
+            symbol: symbol.into(),
+            exponent,
+        }
+    }
+
+    /// metadata of the bank module given `(denom, exponent)` of its units,
+    /// the exponent is the one of the display unit and the symbol defaults to the display unit in upper case
+    pub fn from_bank_metadata(
+        base: String,
+        display: String,
+        symbol: String,
+        units: &[(String, u32)],
+    ) -> Self {
+        let exponent = units
+            .iter()
+            .find(|(denom, _)| *denom == display)
+            .map(|(_, exponent)| *exponent)
+            .unwrap_or_default();
+        let symbol = if symbol.is_empty() {
+            display.to_uppercase()
+        } else {
+            symbol
+        };
+        Self {
+            base,
+            display,
+            symbol,
+            exponent,
+        }
+    }
+
+    /// amount in base units formatted in display units, e.g. "1.5" for 150000000 basecro
+    pub fn format_amount(&self, amount: u128) -> String {
+        let unit = 10u128.pow(self.exponent);
+        let whole = amount / unit;
+        let fraction = amount % unit;
+        if fraction == 0 {
+            whole.to_string()
+        } else {
+            let fraction = format!("{:0width$}", fraction, width = self.exponent as usize);
+            format!("{}.{}", whole, fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<BankMetadata> for DenomMetadata {
+    fn from(metadata: BankMetadata) -> Self {
+        let units: Vec<(String, u32)> = metadata
+            .denom_units
+            .into_iter()
+            .map(|unit| (unit.denom, unit.exponent))
+            .collect();
+        Self::from_bank_metadata(metadata.base, metadata.display, metadata.symbol, &units)
+    }
+}
+
+/// path (e.g. "transfer/channel-0") and base denomination of a token received over IBC
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenomTrace {
+    /// chain of port/channel identifiers the token was sent through
+    pub path: String,
+    /// denomination on the source chain
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    /// "ibc/<hash>" denomination, the hash is SHA256 of the full trace in upper case hex
+    pub fn ibc_denom(&self) -> String {
+        if self.path.is_empty() {
+            return self.base_denom.clone();
+        }
+        let hash = sha256::Hash::hash(self.to_string().as_bytes());
+        let hex: String = hash
+            .into_inner()
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect();
+        format!("{}{}", IBC_DENOM_PREFIX, hex)
+    }
+}
+
+impl fmt::Display for DenomTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.base_denom)
+        } else {
+            write!(f, "{}/{}", self.path, self.base_denom)
+        }
+    }
+}
+
+impl FromStr for DenomTrace {
+    type Err = Error;
+
+    /// parse a full trace like "transfer/channel-0/uatom", leading port/channel pairs form the path
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.starts_with(IBC_DENOM_PREFIX) {
+            return Err(Error::InputError(format!("invalid denom trace: {}", s)));
+        }
+        let parts: Vec<&str> = s.split('/').collect();
+        let mut path_len = 0;
+        while parts.len() > path_len + 2 && parts[path_len + 1].starts_with("channel-") {
+            path_len += 2;
+        }
+        Ok(Self {
+            path: parts[..path_len].join("/"),
+            base_denom: parts[path_len..].join("/"),
+        })
+    }
+}
+
+/// denomination metadata and the IBC denom traces known per chain
+#[derive(Debug, Clone)]
+pub struct DenomRegistry {
+    metadata: HashMap<String, DenomMetadata>,
+    /// traces by chain id and IBC denomination, channel identifiers are specific to a chain
+    traces: HashMap<String, HashMap<String, DenomTrace>>,
+}
+
+impl Default for DenomRegistry {
+    /// registry of well-known denominations
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for (base, display, symbol, exponent) in BUILTIN_DENOMS {
+            registry.add_metadata(DenomMetadata::new(*base, *display, *symbol, *exponent));
+        }
+        registry
+    }
+}
+
+impl DenomRegistry {
+    /// create an empty registry
+    pub fn empty() -> Self {
+        Self {
+            metadata: HashMap::new(),
+            traces: HashMap::new(),
+        }
+    }
+
+    /// add or replace metadata of a base denomination
+    pub fn add_metadata(&mut self, metadata: DenomMetadata) -> &mut Self {
+        self.metadata.insert(metadata.base.clone(), metadata);
+        self
+    }
+
+    /// add an IBC denom trace of the chain `chain_id`, e.g. after querying it from the chain
+    pub fn add_trace(&mut self, chain_id: &str, trace: DenomTrace) -> &mut Self {
+        self.traces
+            .entry(chain_id.to_string())
+            .or_default()
+            .insert(trace.ibc_denom(), trace);
+        self
+    }
+
+    /// known trace of an "ibc/<hash>" denomination on the chain `chain_id`,
+    /// the hash is not case sensitive
+    pub fn trace(&self, chain_id: &str, ibc_denom: &str) -> Option<&DenomTrace> {
+        self.traces
+            .get(chain_id)?
+            .get(&canonical_ibc_denom(ibc_denom))
+    }
+
+    /// metadata of a denomination on the chain `chain_id`,
+    /// IBC denominations are resolved through the known traces of the chain
+    pub fn metadata(&self, chain_id: &str, denom: &str) -> Option<&DenomMetadata> {
+        let base = match self.trace(chain_id, denom) {
+            Some(trace) => trace.base_denom.as_str(),
+            None => denom,
+        };
+        self.metadata.get(base)
+    }
+
+    /// human readable coin of the chain `chain_id`,
+    /// e.g. "1.2 ATOM" for 1200000 of an IBC denomination of uatom
+    pub fn format_coin(&self, chain_id: &str, coin: &Coin) -> String {
+        match self.metadata(chain_id, coin.denom()) {
+            Some(metadata) => format!(
+                "{} {}",
+                metadata.format_amount(coin.amount()),
+                metadata.symbol
+            ),
+            None => coin.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_denom_trace() {
+        let trace: DenomTrace = "transfer/channel-0/uatom".parse().unwrap();
+        assert_eq!(trace.path, "transfer/channel-0");
+        assert_eq!(trace.base_denom, "uatom");
+        assert_eq!(
+            trace.ibc_denom(),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+
+        let trace: DenomTrace = "transfer/channel-1/transfer/channel-2/gamm/pool/1"
+            .parse()
+            .unwrap();
+        assert_eq!(trace.path, "transfer/channel-1/transfer/channel-2");
+        assert_eq!(trace.base_denom, "gamm/pool/1");
+
+        let trace: DenomTrace = "basecro".parse().unwrap();
+        assert_eq!(trace.path, "");
+        assert_eq!(trace.ibc_denom(), "basecro");
+    }
+
+    #[test]
+    fn test_format_coin() {
+        let mut registry = DenomRegistry::default();
+        let trace: DenomTrace = "transfer/channel-0/uatom".parse().unwrap();
+        let coin = Coin::new(1_200_000, trace.ibc_denom()).unwrap();
+        assert_eq!(registry.format_coin("chainmain-1", &coin), coin.to_string());
+
+        registry.add_trace("chainmain-1", trace);
+        assert_eq!(registry.format_coin("chainmain-1", &coin), "1.2 ATOM");
+        // channel-0 of another chain leads elsewhere
+        assert_eq!(registry.format_coin("osmosis-1", &coin), coin.to_string());
+        let coin = Coin::new(100_000_000, "basecro").unwrap();
+        assert_eq!(registry.format_coin("chainmain-1", &coin), "1 CRO");
+        assert_eq!(registry.format_coin("osmosis-1", &coin), "1 CRO");
+    }
+
+    #[test]
+    fn test_trace_lookup_ignores_hash_case() {
+        let mut registry = DenomRegistry::empty();
+        let trace: DenomTrace = "transfer/channel-0/uatom".parse().unwrap();
+        registry.add_trace("chainmain-1", trace.clone());
+        assert_eq!(
+            registry.trace(
+                "chainmain-1",
+                "ibc/27394fb092d2eccd56123c74f36e4c1f926001ceada9ca97ea622b25f41e5eb2"
+            ),
+            Some(&trace)
+        );
+        assert_eq!(
+            registry.trace(
+                "chainmain-1",
+                "IBC/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+            ),
+            Some(&trace)
+        );
+        assert_eq!(registry.trace("osmosis-1", &trace.ibc_denom()), None);
+    }
+}
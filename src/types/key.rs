@@ -3,7 +3,7 @@ use hdwallet::secp256k1;
 use hdwallet::ExtendedPrivKey;
 use secp256k1::{All, Secp256k1};
 use secp256k1::{Error as SecpError, PublicKey as InnerPublicKey, SecretKey};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::string::ToString;
 
 /// Private key
@@ -15,7 +15,7 @@ pub struct PrivateKey(SecretKey);
 pub struct PublicKey(InnerPublicKey);
 
 /// public key format used in transfer transaction
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PublicKeyWrap {
     #[serde(rename = "type")]
     p_type: String,
@@ -31,6 +31,13 @@ impl From<PublicKey> for PublicKeyWrap {
     }
 }
 
+impl PublicKeyWrap {
+    /// decode the wrapped public key
+    pub fn public_key(&self) -> Result<PublicKey, Error> {
+        PublicKey::from_base64_str(&self.value)
+    }
+}
+
 impl AsRef<SecretKey> for PrivateKey {
     fn as_ref(&self) -> &SecretKey {
         &self.0
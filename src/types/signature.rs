@@ -1,30 +1,50 @@
 use crate::error::Error;
 use crate::types::basic::Fee;
 use crate::types::key::PublicKeyWrap;
-use crate::utils::codec::serde_to_str;
-use serde::Serialize;
+use crate::utils::codec::{serde_from_str, serde_to_str};
+use bitcoin_hashes::{sha256, Hash, HashEngine};
+use hdwallet::secp256k1::{Message, Secp256k1, Signature as InnerSignature};
+use serde::{Deserialize, Serialize};
 
 /// Signature used in Tx
-#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Signature {
     /// signature payload
     pub signature: String,
     /// public key
     pub pub_key: PublicKeyWrap,
-    /// global nonce
+    /// global nonce, omitted by some wallets
+    #[serde(default)]
     pub account_number: u64,
-    /// local nonce
+    /// local nonce, omitted by some wallets
+    #[serde(default)]
     pub sequence: u64,
 }
 
+impl Signature {
+    /// verify the signature of a signing payload (e.g. `SignDoc::encode` output)
+    pub fn verify(&self, msg: &[u8]) -> Result<(), Error> {
+        let raw = base64::decode(&self.signature)
+            .map_err(|_e| Error::InputError("invalid signature input".to_string()))?;
+        let signature = InnerSignature::from_compact(&raw)?;
+        let public_key = self.pub_key.public_key()?;
+        let mut engine = sha256::Hash::engine();
+        engine.input(msg);
+        let hash = sha256::Hash::from_engine(engine);
+        let message = Message::from_slice(hash.as_inner())?;
+        Secp256k1::verification_only().verify(&message, &signature, public_key.as_ref())?;
+        Ok(())
+    }
+}
+
 /// signing payload
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SignDoc<M: Serialize> {
     /// global nonce
-    #[serde(serialize_with = "serde_to_str")]
+    #[serde(serialize_with = "serde_to_str", deserialize_with = "serde_from_str")]
     pub account_number: u64,
     /// local nonce
-    #[serde(serialize_with = "serde_to_str")]
+    #[serde(serialize_with = "serde_to_str", deserialize_with = "serde_from_str")]
     pub sequence: u64,
     /// network identifier
     pub chain_id: String,
@@ -0,0 +1,509 @@
+use crate::types::broadcast::{BroadcastResult, Event, EventAttribute};
+use crate::utils::codec::{serde_from_base64, serde_from_base64_list, serde_from_str};
+use serde::{de, Deserialize, Deserializer};
+
+/// serde from ABCI events of the Tendermint v0.34 JSON-RPC endpoints, which encode attribute
+/// keys and values in base64, `null` gives no events
+fn serde_from_abci_events<'de, D>(deserializer: D) -> Result<Vec<Event>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct RawAttribute {
+        key: Option<String>,
+        value: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct RawEvent {
+        #[serde(rename = "type")]
+        kind: String,
+        attributes: Option<Vec<RawAttribute>>,
+    }
+
+    fn decode<E: de::Error>(value: Option<String>) -> Result<String, E> {
+        let bytes = base64::decode(value.unwrap_or_default()).map_err(E::custom)?;
+        String::from_utf8(bytes).map_err(E::custom)
+    }
+
+    let events = Option::<Vec<RawEvent>>::deserialize(deserializer)?.unwrap_or_default();
+    events
+        .into_iter()
+        .map(|event| {
+            let attributes = event
+                .attributes
+                .unwrap_or_default()
+                .into_iter()
+                .map(|attribute| {
+                    Ok(EventAttribute {
+                        key: decode(attribute.key)?,
+                        value: decode(attribute.value)?,
+                    })
+                })
+                .collect::<Result<_, D::Error>>()?;
+            Ok(Event {
+                kind: event.kind,
+                attributes,
+            })
+        })
+        .collect()
+}
+
+/// public key in Tendermint JSON
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PubKey {
+    /// key type, e.g. "tendermint/PubKeyEd25519"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// base64 encoded key
+    pub value: String,
+}
+
+/// identity of a node
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// node id
+    pub id: String,
+    /// p2p listen address
+    pub listen_addr: String,
+    /// chain id
+    pub network: String,
+    /// Tendermint version
+    pub version: String,
+    /// node name
+    pub moniker: String,
+}
+
+/// sync state of a node
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SyncInfo {
+    /// hash of the latest block in upper case hex
+    pub latest_block_hash: String,
+    /// application hash after the latest block
+    pub latest_app_hash: String,
+    /// height of the latest block
+    #[serde(deserialize_with = "serde_from_str")]
+    pub latest_block_height: u64,
+    /// time of the latest block in RFC 3339
+    pub latest_block_time: String,
+    /// lowest height the node has, higher than 1 on pruned or state synced nodes
+    #[serde(default, deserialize_with = "serde_from_str")]
+    pub earliest_block_height: u64,
+    /// whether the node is still syncing
+    pub catching_up: bool,
+}
+
+/// validator key of a node
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorInfo {
+    /// consensus address in upper case hex
+    pub address: String,
+    /// consensus public key
+    pub pub_key: PubKey,
+    /// voting power, 0 if the node is not a validator
+    #[serde(deserialize_with = "serde_from_str")]
+    pub voting_power: u64,
+}
+
+/// response of `status`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    /// identity of the node
+    pub node_info: NodeInfo,
+    /// sync state
+    pub sync_info: SyncInfo,
+    /// validator key
+    pub validator_info: ValidatorInfo,
+}
+
+/// connected peer
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Peer {
+    /// identity of the peer
+    pub node_info: NodeInfo,
+    /// whether the connection was initiated by this node
+    pub is_outbound: bool,
+    /// IP address of the peer
+    pub remote_ip: String,
+}
+
+/// response of `net_info`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NetInfo {
+    /// whether the node accepts connections
+    pub listening: bool,
+    /// p2p listen addresses
+    pub listeners: Vec<String>,
+    /// number of connected peers
+    #[serde(deserialize_with = "serde_from_str")]
+    pub n_peers: u64,
+    /// connected peers
+    pub peers: Vec<Peer>,
+}
+
+/// header of the block parts
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct PartSetHeader {
+    /// number of parts
+    pub total: u32,
+    /// merkle root of the parts
+    pub hash: String,
+}
+
+/// block hash and parts
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlockId {
+    /// block hash in upper case hex, empty for the block before the first one
+    pub hash: String,
+    /// block parts
+    pub parts: PartSetHeader,
+}
+
+/// block header
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// chain id
+    pub chain_id: String,
+    /// block height
+    #[serde(deserialize_with = "serde_from_str")]
+    pub height: u64,
+    /// block time in RFC 3339
+    pub time: String,
+    /// id of the previous block
+    pub last_block_id: BlockId,
+    /// merkle root of the transactions
+    pub data_hash: String,
+    /// hash of the validators of this block
+    pub validators_hash: String,
+    /// hash of the validators of the next block
+    pub next_validators_hash: String,
+    /// application hash after the previous block
+    pub app_hash: String,
+    /// consensus address of the proposer in upper case hex
+    pub proposer_address: String,
+}
+
+/// transactions of a block
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockData {
+    /// raw transaction bytes
+    #[serde(deserialize_with = "serde_from_base64_list")]
+    pub txs: Vec<Vec<u8>>,
+}
+
+/// block header and transactions
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// block header
+    pub header: Header,
+    /// block transactions
+    pub data: BlockData,
+}
+
+/// response of `block`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockResponse {
+    /// block hash and parts
+    pub block_id: BlockId,
+    /// the block
+    pub block: Block,
+}
+
+/// ABCI result of a transaction (CheckTx or DeliverTx)
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct TxResult {
+    /// 0 on success
+    #[serde(default)]
+    pub code: u32,
+    /// module the code belongs to
+    #[serde(default)]
+    pub codespace: String,
+    /// execution log, the error message for failed transactions
+    #[serde(default)]
+    pub log: String,
+    /// data returned by the messages
+    #[serde(default, deserialize_with = "serde_from_base64")]
+    pub data: Vec<u8>,
+    /// gas limit of the transaction
+    #[serde(default, deserialize_with = "serde_from_str")]
+    pub gas_wanted: u64,
+    /// gas used executing the transaction
+    #[serde(default, deserialize_with = "serde_from_str")]
+    pub gas_used: u64,
+    /// events emitted executing the transaction
+    #[serde(default, deserialize_with = "serde_from_abci_events")]
+    pub events: Vec<Event>,
+}
+
+impl TxResult {
+    fn apply_to(&self, result: &mut BroadcastResult) {
+        result.code = self.code;
+        result.codespace = self.codespace.clone();
+        result.raw_log = self.log.clone();
+        result.gas_wanted = self.gas_wanted;
+        result.gas_used = self.gas_used;
+        result.events.extend(self.events.iter().cloned());
+    }
+}
+
+/// response of `block_results`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockResults {
+    /// block height
+    #[serde(deserialize_with = "serde_from_str")]
+    pub height: u64,
+    /// results of the transactions in block order
+    #[serde(default, deserialize_with = "serde_from_nullable")]
+    pub txs_results: Vec<TxResult>,
+    /// events of BeginBlock, e.g. minted rewards
+    #[serde(default, deserialize_with = "serde_from_abci_events")]
+    pub begin_block_events: Vec<Event>,
+    /// events of EndBlock, e.g. completed unbondings
+    #[serde(default, deserialize_with = "serde_from_abci_events")]
+    pub end_block_events: Vec<Event>,
+}
+
+/// validator of a block
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Validator {
+    /// consensus address in upper case hex
+    pub address: String,
+    /// consensus public key
+    pub pub_key: PubKey,
+    /// voting power
+    #[serde(deserialize_with = "serde_from_str")]
+    pub voting_power: u64,
+    /// priority of becoming the next proposer
+    #[serde(deserialize_with = "serde_from_str")]
+    pub proposer_priority: i64,
+}
+
+/// response of `validators`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Validators {
+    /// height of the validator set
+    #[serde(deserialize_with = "serde_from_str")]
+    pub block_height: u64,
+    /// validators of the page
+    pub validators: Vec<Validator>,
+    /// number of validators of the page
+    #[serde(deserialize_with = "serde_from_str")]
+    pub count: u64,
+    /// number of validators of all pages
+    #[serde(deserialize_with = "serde_from_str")]
+    pub total: u64,
+}
+
+/// committed transaction, response of `tx` and item of `tx_search`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TxInfo {
+    /// transaction hash in upper case hex
+    pub hash: String,
+    /// block height
+    #[serde(deserialize_with = "serde_from_str")]
+    pub height: u64,
+    /// position of the transaction in the block
+    pub index: u32,
+    /// execution result
+    pub tx_result: TxResult,
+    /// raw transaction bytes
+    #[serde(deserialize_with = "serde_from_base64")]
+    pub tx: Vec<u8>,
+}
+
+impl TxInfo {
+    /// the execution result as a broadcast result, `timestamp` is not set
+    pub fn result(&self) -> BroadcastResult {
+        let mut result = BroadcastResult {
+            hash: self.hash.clone(),
+            height: self.height,
+            ..Default::default()
+        };
+        self.tx_result.apply_to(&mut result);
+        result
+    }
+}
+
+/// response of `tx_search`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TxSearch {
+    /// transactions of the page
+    pub txs: Vec<TxInfo>,
+    /// number of transactions of all pages
+    #[serde(deserialize_with = "serde_from_str")]
+    pub total_count: u64,
+}
+
+/// order of `tx_search` results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// oldest first
+    Asc,
+    /// newest first
+    Desc,
+}
+
+impl Order {
+    /// name in RPC requests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Order::Asc => "asc",
+            Order::Desc => "desc",
+        }
+    }
+}
+
+/// result of `abci_query`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AbciQuery {
+    /// 0 on success
+    #[serde(default)]
+    pub code: u32,
+    /// module the code belongs to
+    #[serde(default)]
+    pub codespace: String,
+    /// query log, the error message for failed queries
+    #[serde(default)]
+    pub log: String,
+    /// queried key
+    #[serde(default, deserialize_with = "serde_from_base64")]
+    pub key: Vec<u8>,
+    /// value, e.g. a protobuf encoded query response
+    #[serde(default, deserialize_with = "serde_from_base64")]
+    pub value: Vec<u8>,
+    /// height the query was executed at
+    #[serde(default, deserialize_with = "serde_from_str")]
+    pub height: u64,
+}
+
+/// response of `broadcast_tx_sync` and `broadcast_tx_async`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastTxResponse {
+    /// CheckTx code, always 0 for `broadcast_tx_async`
+    #[serde(default)]
+    pub code: u32,
+    /// module the code belongs to
+    #[serde(default)]
+    pub codespace: String,
+    /// CheckTx log
+    #[serde(default)]
+    pub log: String,
+    /// transaction hash in upper case hex
+    pub hash: String,
+}
+
+impl From<BroadcastTxResponse> for BroadcastResult {
+    fn from(response: BroadcastTxResponse) -> Self {
+        Self {
+            hash: response.hash,
+            code: response.code,
+            codespace: response.codespace,
+            raw_log: response.log,
+            ..Default::default()
+        }
+    }
+}
+
+/// response of `broadcast_tx_commit`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastTxCommitResponse {
+    /// CheckTx result
+    pub check_tx: TxResult,
+    /// DeliverTx result, empty if CheckTx failed
+    pub deliver_tx: TxResult,
+    /// transaction hash in upper case hex
+    pub hash: String,
+    /// block height, 0 if CheckTx failed
+    #[serde(deserialize_with = "serde_from_str")]
+    pub height: u64,
+}
+
+impl From<BroadcastTxCommitResponse> for BroadcastResult {
+    fn from(response: BroadcastTxCommitResponse) -> Self {
+        let mut result = Self {
+            hash: response.hash,
+            height: response.height,
+            ..Default::default()
+        };
+        response.check_tx.apply_to(&mut result);
+        if result.is_ok() {
+            response.deliver_tx.apply_to(&mut result);
+        }
+        result
+    }
+}
+
+/// serde from a list, `null` gives an empty list
+fn serde_from_nullable<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_block_results() {
+        let response = serde_json::json!({
+            "height": "1024",
+            "txs_results": [{
+                "code": 0,
+                "data": "CgYKBHNlbmQ=",
+                "log": "[]",
+                "info": "",
+                "gas_wanted": "200000",
+                "gas_used": "62545",
+                "events": [{
+                    "type": "transfer",
+                    "attributes": [
+                        {"key": "cmVjaXBpZW50", "value": "Y3JvMWZqNmpwbXV5a3ZyYTRreHJ3MGNwMjBlNHZ4NHI4ZWRhOHEzeW45", "index": true},
+                        {"key": "YW1vdW50", "value": null, "index": true}
+                    ]
+                }],
+                "codespace": ""
+            }],
+            "begin_block_events": null,
+            "end_block_events": [],
+            "validator_updates": null,
+            "consensus_param_updates": null
+        });
+        let results: BlockResults = serde_json::from_value(response).unwrap();
+        assert_eq!(results.height, 1024);
+        assert!(results.begin_block_events.is_empty());
+        let tx_result = &results.txs_results[0];
+        assert_eq!(tx_result.gas_used, 62545);
+        assert_eq!(tx_result.data, b"\n\x06\n\x04send");
+        assert_eq!(
+            tx_result.events[0].attribute("recipient"),
+            Some("cro1fj6jpmuykvra4kxrw0cp20e4vx4r8eda8q3yn9")
+        );
+        assert_eq!(tx_result.events[0].attribute("amount"), Some(""));
+
+        // attributes are always base64, plain text is rejected
+        let invalid = serde_json::json!({
+            "height": "1",
+            "end_block_events": [{"type": "t", "attributes": [{"key": "recipient", "value": "cro1"}]}]
+        });
+        assert!(serde_json::from_value::<BlockResults>(invalid).is_err());
+    }
+
+    #[test]
+    fn test_broadcast_tx_commit() {
+        let response = serde_json::json!({
+            "check_tx": {"code": 0, "data": null, "log": "[]", "gas_wanted": "200000", "gas_used": "40000", "events": []},
+            "deliver_tx": {"code": 5, "codespace": "sdk", "data": null, "log": "insufficient funds", "gas_wanted": "200000", "gas_used": "52000", "events": null},
+            "hash": "BAFDAE5BE6EAEF9DCFA80CE6EDA665ABED5DA54DFC2AD98153F96B9B04FBAB1D",
+            "height": "1024"
+        });
+        let response: BroadcastTxCommitResponse = serde_json::from_value(response).unwrap();
+        let result = BroadcastResult::from(response);
+        assert_eq!(result.height, 1024);
+        assert_eq!(result.code, 5);
+        assert_eq!(result.gas_used, 52000);
+        assert_eq!(result.raw_log, "insufficient funds");
+    }
+}
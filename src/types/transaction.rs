@@ -1,9 +1,10 @@
+use crate::error::Error;
 use crate::types::basic::{Fee, SyncMode};
-use crate::types::signature::Signature;
-use serde::Serialize;
+use crate::types::signature::{SignDoc, Signature};
+use serde::{Deserialize, Serialize};
 
 /// tx in transfer transaction
-#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Tx<M: Serialize> {
     /// messages to be executed
     #[serde(rename = "msg")]
@@ -16,8 +17,34 @@ pub struct Tx<M: Serialize> {
     pub signatures: Vec<Signature>,
 }
 
+impl<M: Serialize + Clone> Tx<M> {
+    /// signing payload of the transaction
+    pub fn sign_doc(&self, chain_id: &str, account_number: u64, sequence: u64) -> SignDoc<M> {
+        SignDoc {
+            account_number,
+            sequence,
+            chain_id: chain_id.to_string(),
+            memo: self.memo.clone(),
+            fee: self.fee.clone(),
+            msgs: self.messages.clone(),
+        }
+    }
+
+    /// verify the signatures of a single-signer transaction
+    pub fn verify(&self, chain_id: &str, account_number: u64, sequence: u64) -> Result<(), Error> {
+        if self.signatures.is_empty() {
+            return Err(Error::InputError("transaction is not signed".to_string()));
+        }
+        let raw_doc = self.sign_doc(chain_id, account_number, sequence).encode()?;
+        for signature in &self.signatures {
+            signature.verify(&raw_doc)?;
+        }
+        Ok(())
+    }
+}
+
 /// transfer transaction
-#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Transaction<M: Serialize> {
     /// transaction to be broadcasted
     pub tx: Tx<M>,
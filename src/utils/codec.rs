@@ -1,5 +1,7 @@
-use serde::Serializer;
+use bitcoin_hashes::{sha256, Hash};
+use serde::{de, Deserialize, Deserializer, Serializer};
 use std::fmt;
+use std::str::FromStr;
 
 /// serde from <T> which impled `Display` to string
 pub fn serde_to_str<T, S>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -9,3 +11,59 @@ where
 {
     serializer.serialize_str(&*value.to_string())
 }
+
+/// serde from string to <T> which impled `FromStr`
+pub fn serde_from_str<'de, T, D>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(de::Error::custom)
+}
+
+/// serde from base64 string to bytes, `null` gives empty bytes
+pub fn serde_from_base64<'de, D>(deserializer: D) -> std::result::Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+    base64::decode(&value).map_err(de::Error::custom)
+}
+
+/// serde from a list of base64 strings to bytes, `null` gives an empty list
+pub fn serde_from_base64_list<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Option::<Vec<String>>::deserialize(deserializer)?.unwrap_or_default();
+    values
+        .iter()
+        .map(|value| base64::decode(value).map_err(de::Error::custom))
+        .collect()
+}
+
+/// hex encoded SHA-256 hash of transaction bytes in upper case, as Tendermint shows it
+pub fn tx_hash(tx_bytes: &[u8]) -> String {
+    sha256::Hash::hash(tx_bytes)
+        .into_inner()
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tx_hash() {
+        assert_eq!(
+            tx_hash(b""),
+            "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855"
+        );
+    }
+}